@@ -1,2 +1,25 @@
 pub mod part1;
 pub mod part2;
+#[cfg(test)]
+mod tests;
+
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution, SolveError};
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = Vec<i64>;
+
+    fn parse(input: &str) -> Result<Vec<i64>, ParseError> {
+        parse::integer_lines(input)
+    }
+
+    fn part1(input: &Vec<i64>) -> Result<Answer, SolveError> {
+        Ok(part1::solve(input))
+    }
+
+    fn part2(input: &Vec<i64>) -> Result<Answer, SolveError> {
+        Ok(part2::solve(input))
+    }
+}
@@ -1,15 +1,9 @@
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-
-pub fn run_puzzle() {
-    let file = File::open("input_day1.txt").expect("Failed to open input_day1.txt");
-    let br = BufReader::new(file);
-
-    let vec: Vec<i64> = br.lines().map(|line| line.expect("Line parsing failed").parse::<i64>().expect("Line => i64 failed")).collect();
+use crate::solution::Answer;
 
+pub fn solve(vec: &[i64]) -> Answer {
     let mut total_fuel = 0;
-    for v in &vec {
-        let mut fuel_input = v.clone();
+    for v in vec {
+        let mut fuel_input = *v;
         loop {
             fuel_input = if (fuel_input / 3) - 2 < 0 { 0 } else { (fuel_input / 3) - 2 };
             if fuel_input == 0 {
@@ -19,5 +13,5 @@ pub fn run_puzzle() {
         }
     }
 
-    println!("Total : {}", total_fuel);
+    total_fuel.into()
 }
@@ -0,0 +1,31 @@
+use super::Day1;
+use crate::solution::Answer;
+use crate::testing::{self, check};
+
+#[test]
+fn fuel_of_the_examples() {
+    assert_eq!(testing::part1::<Day1>("12"), Answer::Number(2));
+    assert_eq!(testing::part1::<Day1>("14"), Answer::Number(2));
+    assert_eq!(testing::part1::<Day1>("1969"), Answer::Number(654));
+    assert_eq!(testing::part1::<Day1>("100756"), Answer::Number(33583));
+    assert_eq!(testing::part1::<Day1>("12\n14\n1969\n100756\n"), Answer::Number(34241));
+}
+
+#[test]
+fn fuel_for_the_fuel_of_the_examples() {
+    assert_eq!(testing::part2::<Day1>("14"), Answer::Number(2));
+    assert_eq!(testing::part2::<Day1>("1969"), Answer::Number(966));
+    assert_eq!(testing::part2::<Day1>("100756"), Answer::Number(50346));
+}
+
+#[test]
+fn fuel_for_the_fuel_is_never_less() {
+    check(|rng| {
+        let masses: Vec<String> = (0..rng.range(1..10)).map(|_| rng.range(1..1_000_000).to_string()).collect();
+        let input = masses.join("\n");
+        match (testing::part1::<Day1>(&input), testing::part2::<Day1>(&input)) {
+            (Answer::Number(fuel), Answer::Number(total)) => assert!(total >= fuel),
+            answers => panic!("unexpected answers {:?}", answers),
+        }
+    });
+}
@@ -1,2 +1,37 @@
 pub mod part1;
 pub mod part2;
+#[cfg(test)]
+mod tests;
+
+use crate::grid::Grid;
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution, SolveError};
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = Grid<bool>;
+
+    fn parse(input: &str) -> Result<Grid<bool>, ParseError> {
+        let map = Grid::parse(input, |c| match c {
+            '#' => Some(true),
+            '.' => Some(false),
+            _ => None,
+        })?;
+
+        // The station has to see at least one other asteroid
+        if map.iter().filter(|(_, asteroid)| **asteroid).count() < 2 {
+            return Err(parse::whole(input).error("the map needs at least 2 asteroids"));
+        }
+
+        Ok(map)
+    }
+
+    fn part1(input: &Grid<bool>) -> Result<Answer, SolveError> {
+        Ok(part1::solve(input))
+    }
+
+    fn part2(input: &Grid<bool>) -> Result<Answer, SolveError> {
+        part2::solve(input)
+    }
+}
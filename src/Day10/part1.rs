@@ -1,6 +1,6 @@
+use crate::grid::Grid;
+use crate::solution::Answer;
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::Read;
 
 fn gcd(a: i64, b: i64) -> i64 {
     let (mut tmp_a, mut tmp_b) = (a, b);
@@ -17,29 +17,15 @@ fn gcd(a: i64, b: i64) -> i64 {
     }
 }
 
-pub fn run_puzzle() {
-    let mut file = File::open("input_day10.txt").expect("Failed to open input_day10.txt");
-    let mut map_string = String::new();
-    file.read_to_string(&mut map_string).unwrap();
+// Asteroid seeing the most other asteroids, with how many it sees
+pub fn best_station(aster_list: &HashSet<(i64, i64)>) -> ((i64, i64), i64) {
+    let (mut station, mut max_visible) = ((0, 0), 0);
 
-    let map: Vec<Vec<u8>> = map_string.lines().map(|line| line.trim().as_bytes().to_vec()).collect();
+    // Scanned in x then y order, so a tie always goes to the same asteroid
+    let mut candidates: Vec<(i64, i64)> = aster_list.iter().copied().collect();
+    candidates.sort_unstable();
 
-    let width = map[0].len();
-    let height = map.len();
-
-    let mut aster_list: HashSet<(i64, i64)> = HashSet::new();
-    for x in 0..width {
-        for y in 0..height {
-            if map[y][x] == '#' as u8 {
-                aster_list.insert((x as i64, y as i64));
-            }
-        }
-    }
-
-    let mut max_visible = 0;
-    let (mut max_x, mut max_y) = (0, 0);
-
-    for (x, y) in aster_list.iter().copied() {
+    for (x, y) in candidates {
         let mut visible = 0;
         for (ox, oy) in aster_list.iter().copied() {
             if x == ox && y == oy {
@@ -68,11 +54,23 @@ pub fn run_puzzle() {
         }
 
         if visible > max_visible {
+            station = (x, y);
             max_visible = visible;
-            max_x = x;
-            max_y = y;
         }
     }
 
-    println!("Max asteroids in view is: ({},{}) with {}", max_x, max_y, max_visible);
+    (station, max_visible)
+}
+
+pub fn solve(map: &Grid<bool>) -> Answer {
+    let mut aster_list: HashSet<(i64, i64)> = HashSet::new();
+    for (point, asteroid) in map.iter() {
+        if *asteroid {
+            aster_list.insert(point);
+        }
+    }
+
+    let (_, max_visible) = best_station(&aster_list);
+
+    max_visible.into()
 }
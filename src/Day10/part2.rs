@@ -1,6 +1,7 @@
+use super::part1;
+use crate::grid::Grid;
+use crate::solution::{Answer, SolveError};
 use std::collections::HashSet;
-use std::fs::File;
-use std::io::Read;
 
 fn gcd(a: i64, b: i64) -> i64 {
     let (mut tmp_a, mut tmp_b) = (a, b);
@@ -17,26 +18,15 @@ fn gcd(a: i64, b: i64) -> i64 {
     }
 }
 
-pub fn run_puzzle() {
-    let mut file = File::open("input_day10.txt").expect("Failed to open input_day10.txt");
-    let mut map_string = String::new();
-    file.read_to_string(&mut map_string).unwrap();
-
-    let map: Vec<Vec<u8>> = map_string.lines().map(|line| line.trim().as_bytes().to_vec()).collect();
-
-    let width = map[0].len();
-    let height = map.len();
-
+pub fn solve(map: &Grid<bool>) -> Result<Answer, SolveError> {
     let mut aster_list: HashSet<(i64, i64)> = HashSet::new();
-    for x in 0..width {
-        for y in 0..height {
-            if map[y][x] == '#' as u8 {
-                aster_list.insert((x as i64, y as i64));
-            }
+    for (point, asteroid) in map.iter() {
+        if *asteroid {
+            aster_list.insert(point);
         }
     }
 
-    let (x, y) = (22, 25);
+    let ((x, y), _) = part1::best_station(&aster_list);
     let mut dest_count = 0;
 
     while aster_list.len() != 1 {
@@ -82,9 +72,10 @@ pub fn run_puzzle() {
             dest_count += 1;
             // println!("Destroyed asteroid({}) at ({}, {}), degrees:{}", dest_count, v.0, v.1, v.2);
             if dest_count == 200 {
-                println!("Destroyed 200th asteroid at ({}, {})", v.0, v.1);
-                return;
+                return Ok((v.0 * 100 + v.1).into());
             }
         }
     }
+
+    Err(SolveError::Failed(String::from("Fewer than 200 asteroids to vaporize")))
 }
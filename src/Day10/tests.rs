@@ -0,0 +1,50 @@
+use super::Day10;
+use crate::solution::{Answer, Solution};
+use crate::testing::{part1, part2};
+
+const SMALL: &str = ".#..#
+.....
+#####
+....#
+...##
+";
+
+const LARGE: &str = ".#..##.###...#######
+##.############..##.
+.#.######.#########.
+.###.#######.####.#.
+#####.##.#.##.###.##
+..#####..#.#########
+####################
+#.####....###.#.#.##
+##.#################
+#####.##.###..####..
+..######..##.#######
+####.##.####...##..#
+.#####..#.######.###
+##...#.##########...
+#.##########.#######
+.####.#.###.###.#.##
+....##.##.###..#####
+.#.#.###########.###
+#.#.#.#####.####.###
+###.##.####.##.#..##
+";
+
+#[test]
+fn best_stations_of_the_examples() {
+    assert_eq!(part1::<Day10>(SMALL), Answer::Number(8));
+    assert_eq!(part1::<Day10>(LARGE), Answer::Number(210));
+}
+
+#[test]
+fn two_hundredth_vaporised_asteroid() {
+    assert_eq!(part2::<Day10>(LARGE), Answer::Number(802));
+}
+
+#[test]
+fn maps_need_two_asteroids() {
+    assert!(Day10::parse(".\n").is_err());
+    assert!(Day10::parse("#.\n..\n").is_err());
+    assert!(Day10::parse("#.\n.#\n").is_ok());
+}
@@ -0,0 +1,26 @@
+use super::Day11;
+use crate::solution::Answer;
+use crate::testing::part1;
+
+// Program reading the colour under the robot and answering with the given colours and turns
+fn robot(moves: &[(i64, i64)]) -> String {
+    let mut program: Vec<String> = Vec::new();
+    for &(colour, turn) in moves {
+        program.extend(vec![3, 1000, 104, colour, 104, turn].into_iter().map(|v| v.to_string()));
+    }
+    program.push(String::from("99"));
+    program.join(",")
+}
+
+#[test]
+fn painted_panels_of_the_example() {
+    let moves = [(1, 0), (0, 0), (1, 0), (1, 0), (0, 1), (1, 0), (1, 0)];
+    assert_eq!(part1::<Day11>(&robot(&moves)), Answer::Number(6));
+}
+
+#[test]
+fn panels_are_counted_once() {
+    // Turning left four times paints the same four panels twice
+    let moves = [(1, 0); 8];
+    assert_eq!(part1::<Day11>(&robot(&moves)), Answer::Number(4));
+}
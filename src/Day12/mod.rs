@@ -1,2 +1,47 @@
 pub mod part1;
 pub mod part2;
+#[cfg(test)]
+mod tests;
+
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution, SolveError};
+
+pub const INPUT: &str = "<x=-3, y=15, z=-11>
+<x=3, y=13, z=-19>
+<x=-13, y=18, z=-2>
+<x=6, y=0, z=-1>
+";
+
+pub struct Day12;
+
+impl Solution for Day12 {
+    type Input = Vec<[i64; 3]>;
+
+    // Positions are written as <x=-3, y=15, z=-11>
+    fn parse(input: &str) -> Result<Vec<[i64; 3]>, ParseError> {
+        parse::lines(input)
+            .map(|line| {
+                let coords = line.trim().strip_prefix("<")?.strip_suffix(">")?;
+                let values = coords.split(",");
+                if values.len() != 3 {
+                    return Err(coords.error("expected 3 coordinates"));
+                }
+
+                let mut pos = [0; 3];
+                for (i, (value, name)) in values.into_iter().zip(&["x=", "y=", "z="]).enumerate() {
+                    pos[i] = value.trim().strip_prefix(name)?.integer()?;
+                }
+
+                Ok(pos)
+            })
+            .collect()
+    }
+
+    fn part1(input: &Vec<[i64; 3]>) -> Result<Answer, SolveError> {
+        Ok(part1::solve(input))
+    }
+
+    fn part2(input: &Vec<[i64; 3]>) -> Result<Answer, SolveError> {
+        Ok(part2::solve(input))
+    }
+}
@@ -1,7 +1,9 @@
+use crate::solution::Answer;
+
 #[derive(Clone)]
-struct Moon {
-    pos: [i64; 3],
-    vel: [i64; 3],
+pub struct Moon {
+    pub pos: [i64; 3],
+    pub vel: [i64; 3],
 }
 
 impl Moon {
@@ -9,13 +11,6 @@ impl Moon {
         Moon { pos: [x, y, z], vel: [0, 0, 0] }
     }
 
-    fn _print(&self) {
-        println!(
-            "pos=<x={}, y={}, z={}>, vel=<x={}, y={}, z={}>",
-            self.pos[0], self.pos[1], self.pos[2], self.vel[0], self.vel[1], self.vel[2]
-        );
-    }
-
     fn adjust_vel(&mut self, om: &Moon) {
         for i in 0..3 {
             if self.pos[i] != om.pos[i] {
@@ -34,7 +29,7 @@ impl Moon {
         }
     }
 
-    fn energy(&self) -> i64 {
+    pub fn energy(&self) -> i64 {
         let pos_energy: i64 = self.pos.iter().map(|v| if *v < 0 { -*v } else { *v }).sum();
         let vel_energy: i64 = self.vel.iter().map(|v| if *v < 0 { -*v } else { *v }).sum();
 
@@ -42,15 +37,11 @@ impl Moon {
     }
 }
 
-pub fn run_puzzle() {
-    let mut moons = Vec::new();
+// Moons after the given number of steps
+pub fn simulate(positions: &[[i64; 3]], steps: usize) -> Vec<Moon> {
+    let mut moons: Vec<Moon> = positions.iter().map(|p| Moon::new(p[0], p[1], p[2])).collect();
 
-    moons.push(Moon::new(-3, 15, -11));
-    moons.push(Moon::new(3, 13, -19));
-    moons.push(Moon::new(-13, 18, -2));
-    moons.push(Moon::new(6, 0, -1));
-
-    for _ in 0..1000 {
+    for _ in 0..steps {
         // moons.iter().for_each(|m| m.print());
 
         let old_moons = moons.clone();
@@ -64,6 +55,10 @@ pub fn run_puzzle() {
         moons.iter_mut().for_each(|m| m.adjust_pos());
     }
 
-    let total_energy: i64 = moons.iter().map(|m| m.energy()).sum();
-    println!("Total energy: {}", total_energy);
+    moons
+}
+
+pub fn solve(positions: &[[i64; 3]]) -> Answer {
+    let total_energy: i64 = simulate(positions, 1000).iter().map(|m| m.energy()).sum();
+    total_energy.into()
 }
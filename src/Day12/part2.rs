@@ -1,3 +1,4 @@
+use crate::solution::Answer;
 use num::integer::lcm;
 
 #[derive(Clone)]
@@ -30,30 +31,25 @@ impl Moon {
     }
 }
 
-pub fn run_puzzle() {
-    let mut moons = Vec::new();
-
-    moons.push(Moon::new(-3, 15, -11));
-    moons.push(Moon::new(3, 13, -19));
-    moons.push(Moon::new(-13, 18, -2));
-    moons.push(Moon::new(6, 0, -1));
+pub fn solve(positions: &[[i64; 3]]) -> Answer {
+    let mut moons: Vec<Moon> = positions.iter().map(|p| Moon::new(p[0], p[1], p[2])).collect();
 
     let mut periods = [0; 3];
 
     let initial_state = moons.clone();
 
     for round in 0..1000000 {
-        for coor in 0..3 {
-            if periods[coor] == 0 {
+        for (coor, period) in periods.iter_mut().enumerate() {
+            if *period == 0 {
                 let mut found = true;
-                for i in 0..4 {
+                for i in 0..moons.len() {
                     if moons[i].pos[coor] != initial_state[i].pos[coor] || moons[i].vel[coor] != initial_state[i].vel[coor] {
                         found = false;
                         break;
                     }
                 }
                 if found {
-                    periods[coor] = round;
+                    *period = round;
                 }
             }
         }
@@ -75,5 +71,5 @@ pub fn run_puzzle() {
 
     let result = lcm::<i64>(lcm::<i64>(periods[0], periods[1]), periods[2]);
 
-    println!("First repetition: {}", result);
+    result.into()
 }
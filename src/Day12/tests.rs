@@ -0,0 +1,46 @@
+use super::part1::simulate;
+use super::Day12;
+use crate::solution::Answer;
+use crate::testing::{check, part2};
+
+const EXAMPLE: &str = "<x=-1, y=0, z=2>
+<x=2, y=-10, z=-7>
+<x=4, y=-8, z=8>
+<x=3, y=5, z=-1>
+";
+
+const POSITIONS: &[[i64; 3]] = &[[-1, 0, 2], [2, -10, -7], [4, -8, 8], [3, 5, -1]];
+
+#[test]
+fn energy_of_the_example() {
+    let moons = simulate(POSITIONS, 10);
+    assert_eq!(moons.iter().map(|m| m.energy()).sum::<i64>(), 179);
+    assert_eq!(moons[0].pos, [2, 1, -3]);
+    assert_eq!(moons[0].vel, [-3, -2, 1]);
+}
+
+#[test]
+fn cycle_of_the_example() {
+    assert_eq!(part2::<Day12>(EXAMPLE), Answer::Number(2772));
+}
+
+#[test]
+fn momentum_is_conserved() {
+    check(|rng| {
+        let positions: Vec<[i64; 3]> = (0..4).map(|_| [rng.range(-20..20), rng.range(-20..20), rng.range(-20..20)]).collect();
+        let moons = simulate(&positions, rng.range(0..200) as usize);
+        for axis in 0..3 {
+            assert_eq!(moons.iter().map(|m| m.vel[axis]).sum::<i64>(), 0);
+        }
+    });
+}
+
+#[test]
+fn moons_at_rest_have_no_energy() {
+    check(|rng| {
+        // Moons all on the same point never attract each other
+        let position = [rng.range(-20..20), rng.range(-20..20), rng.range(-20..20)];
+        let moons = simulate(&[position; 4], rng.range(0..200) as usize);
+        assert!(moons.iter().all(|m| m.pos == position && m.energy() == 0));
+    });
+}
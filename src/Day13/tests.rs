@@ -0,0 +1,16 @@
+use super::Day13;
+use crate::solution::Answer;
+use crate::testing::{part1, part2};
+
+#[test]
+fn blocks_drawn_on_the_screen() {
+    // Paddle, two blocks, one of them erased again, and the ball
+    let program = "104,1,104,2,104,3,104,6,104,5,104,2,104,7,104,8,104,2,104,7,104,8,104,0,104,0,104,0,104,4,99";
+    assert_eq!(part1::<Day13>(program), Answer::Number(1));
+}
+
+#[test]
+fn score_is_displayed_at_minus_one() {
+    // The first instruction becomes a multiplication once the game is free to play
+    assert_eq!(part2::<Day13>("1,3,3,3,104,-1,104,0,104,12345,99"), Answer::Number(12345));
+}
@@ -1,2 +1,96 @@
 pub mod part1;
 pub mod part2;
+#[cfg(test)]
+mod tests;
+
+use crate::parse::{self, ParseError, Span};
+use crate::solution::{Answer, Solution, SolveError};
+use std::collections::{HashMap, HashSet};
+
+pub struct Element {
+    pub name: String,
+    pub quantity: i64,
+}
+
+// Produced element and the elements it consumes
+pub type Reaction = (Element, Vec<Element>);
+
+// Term like `7 ORE`, with the span of the whole term and of the name
+fn term(span: Span) -> Result<(Span, Span, i64), ParseError> {
+    let span = span.trim();
+    let (quantity, name) = parse::term(span)?;
+    if quantity <= 0 {
+        return Err(span.error(format!("the quantity of {} must be positive", name.text)));
+    }
+    Ok((span, name, quantity))
+}
+
+fn element((_, name, quantity): (Span, Span, i64)) -> Element {
+    Element {
+        name: name.text.to_string(),
+        quantity,
+    }
+}
+
+// Checks the chemicals consumed to make `chemical` don't lead back to it, with every chemical
+// produced and no cycle all the chains end at ORE
+fn check_chain<'a>(chemical: &'a str, producers: &HashMap<&'a str, Vec<(Span<'a>, Span<'a>, i64)>>, path: &mut Vec<&'a str>, checked: &mut HashSet<&'a str>) -> Result<(), ParseError> {
+    path.push(chemical);
+    for &(span, name, _) in &producers[chemical] {
+        if path.contains(&name.text) {
+            return Err(span.error(format!("{} is part of a reaction cycle", name.text)));
+        }
+        if name.text != "ORE" && checked.insert(name.text) {
+            check_chain(name.text, producers, path, checked)?;
+        }
+    }
+    path.pop();
+    Ok(())
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Vec<Reaction>;
+
+    // Every chemical but ORE is produced by exactly one reaction and FUEL is made from ORE without
+    // going through a cycle, the error points at the first term breaking that
+    fn parse(input: &str) -> Result<Vec<Reaction>, ParseError> {
+        let mut reactions = Vec::new();
+        // Consumed terms of every produced chemical
+        let mut producers: HashMap<&str, Vec<(Span, Span, i64)>> = HashMap::new();
+        for line in parse::lines(input) {
+            let (consumed, result) = line.split_once("=>")?;
+            let result = term(result)?;
+            let consumed = consumed.split(",").into_iter().map(term).collect::<Result<Vec<_>, _>>()?;
+
+            if result.1.text == "ORE" {
+                return Err(result.0.error("ORE can't be produced"));
+            }
+            if producers.insert(result.1.text, consumed.clone()).is_some() {
+                return Err(result.0.error(format!("{} is produced by 2 reactions", result.1.text)));
+            }
+            reactions.push((result, consumed));
+        }
+
+        for (_, consumed) in &reactions {
+            if let Some((span, name, _)) = consumed.iter().find(|(_, name, _)| name.text != "ORE" && !producers.contains_key(name.text)) {
+                return Err(span.error(format!("no reaction produces {}", name.text)));
+            }
+        }
+        if !producers.contains_key("FUEL") {
+            return Err(parse::whole(input).error("no reaction produces FUEL"));
+        }
+        check_chain("FUEL", &producers, &mut Vec::new(), &mut HashSet::new())?;
+
+        Ok(reactions.into_iter().map(|(result, consumed)| (element(result), consumed.into_iter().map(element).collect())).collect())
+    }
+
+    fn part1(input: &Vec<Reaction>) -> Result<Answer, SolveError> {
+        Ok(part1::solve(input))
+    }
+
+    fn part2(input: &Vec<Reaction>) -> Result<Answer, SolveError> {
+        Ok(part2::solve(input))
+    }
+}
@@ -1,48 +1,11 @@
+use crate::solution::Answer;
+use crate::Day14::Reaction;
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::Read;
-
-struct Element {
-    name: String,
-    quantity: i64,
-}
-
-impl Element {
-    fn new(name: String, quantity: i64) -> Element {
-        Element { name, quantity }
-    }
-
-    fn from_string(string: &str) -> Element {
-        let quantity_and_elem: Vec<&str> = string.split(' ').collect();
-        if quantity_and_elem.len() != 2 {
-            panic!("String doesn't contain quantity and element");
-        }
-
-        Element::new(quantity_and_elem[1].to_string(), quantity_and_elem[0].parse().unwrap())
-    }
-}
-
-pub fn run_puzzle() {
-    let mut file = File::open("input_day14.txt").expect("Failed to open input_day14.txt");
-    let mut reacts_string = String::new();
-    file.read_to_string(&mut reacts_string).unwrap();
-
-    let mut reactions: Vec<(Element, Vec<Element>)> = Vec::new();
-
-    for line in reacts_string.lines() {
-        let elems_and_res: Vec<String> = line.split("=>").map(|l| l.trim().to_string()).collect();
-        if elems_and_res.len() != 2 {
-            panic!("len() != 2");
-        }
-
-        let elements: Vec<Element> = elems_and_res[0].split(',').map(|e| Element::from_string(e.trim())).collect();
-        let result = Element::from_string(&elems_and_res[1]);
-
-        reactions.push((result, elements));
-    }
 
+// ORE needed to produce the given amount of FUEL, the reactions being checked when parsed
+pub fn evaluate(fuel_to_produce: i64, reactions: &[Reaction]) -> i64 {
     let mut elements_needed: HashMap<String, i64> = HashMap::new();
-    elements_needed.insert("FUEL".to_string(), 1);
+    elements_needed.insert("FUEL".to_string(), fuel_to_produce);
 
     let mut finished = false;
     while !finished {
@@ -52,15 +15,13 @@ pub fn run_puzzle() {
                 continue;
             }
 
-            for r in &reactions {
+            for r in reactions {
                 if r.0.name == *e {
-                    let num_reactions;
-
-                    if *quantity % r.0.quantity != 0 {
-                        num_reactions = (*quantity / r.0.quantity) + 1;
+                    let num_reactions = if *quantity % r.0.quantity != 0 {
+                        (*quantity / r.0.quantity) + 1
                     } else {
-                        num_reactions = *quantity / r.0.quantity;
-                    }
+                        *quantity / r.0.quantity
+                    };
 
                     for n in &r.1 {
                         let elem_entry = new_elements.entry(n.name.to_string()).or_insert(0);
@@ -87,5 +48,9 @@ pub fn run_puzzle() {
         }
     }
 
-    println!("Needs {} ORE", elements_needed[&"ORE".to_string()]);
+    elements_needed[&"ORE".to_string()]
+}
+
+pub fn solve(reactions: &[Reaction]) -> Answer {
+    evaluate(1, reactions).into()
 }
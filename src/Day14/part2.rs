@@ -1,118 +1,27 @@
-use std::collections::HashMap;
-use std::fs::File;
-use std::io::Read;
-
-struct Element {
-    name: String,
-    quantity: i64,
-}
-
-impl Element {
-    fn new(name: String, quantity: i64) -> Element {
-        Element { name, quantity }
-    }
-
-    fn from_string(string: &str) -> Element {
-        let quantity_and_elem: Vec<&str> = string.split(' ').collect();
-        if quantity_and_elem.len() != 2 {
-            panic!("String doesn't contain quantity and element");
-        }
-
-        Element::new(quantity_and_elem[1].to_string(), quantity_and_elem[0].parse().unwrap())
-    }
-}
-
-fn evaluate(fuel_to_produce: i64, reactions: &Vec<(Element, Vec<Element>)>) -> i64 {
-    let mut elements_needed: HashMap<String, i64> = HashMap::new();
-    elements_needed.insert("FUEL".to_string(), fuel_to_produce);
-
-    let mut finished = false;
-    while !finished {
-        let mut new_elements = elements_needed.clone();
-        for (e, quantity) in &elements_needed {
-            if *e == "ORE" || *quantity <= 0 {
-                continue;
-            }
-
-            for r in reactions {
-                if r.0.name == *e {
-                    let num_reactions;
-
-                    if *quantity % r.0.quantity != 0 {
-                        num_reactions = (*quantity / r.0.quantity) + 1;
-                    } else {
-                        num_reactions = *quantity / r.0.quantity;
-                    }
-
-                    for n in &r.1 {
-                        let elem_entry = new_elements.entry(n.name.to_string()).or_insert(0);
-                        *elem_entry += num_reactions * n.quantity;
-                    }
-
-                    let res_entry = new_elements.entry(e.to_string()).or_insert(0);
-                    *res_entry -= num_reactions * r.0.quantity;
-                }
-            }
-        }
-
-        elements_needed = new_elements;
-
-        finished = true;
-        for (e, quantity) in &elements_needed {
-            if e == "ORE" {
-                continue;
-            }
-            if *quantity > 0 {
-                finished = false;
-                break;
-            }
-        }
-    }
-
-    elements_needed[&"ORE".to_string()]
-}
-
-pub fn run_puzzle() {
-    let mut file = File::open("input_day14.txt").expect("Failed to open input_day14.txt");
-    let mut reacts_string = String::new();
-    file.read_to_string(&mut reacts_string).unwrap();
-
-    let mut reactions: Vec<(Element, Vec<Element>)> = Vec::new();
-
-    for line in reacts_string.lines() {
-        let elems_and_res: Vec<String> = line.split("=>").map(|l| l.trim().to_string()).collect();
-        if elems_and_res.len() != 2 {
-            panic!("len() != 2");
-        }
-
-        let elements: Vec<Element> = elems_and_res[0].split(',').map(|e| Element::from_string(e.trim())).collect();
-        let result = Element::from_string(&elems_and_res[1]);
-
-        reactions.push((result, elements));
+use crate::solution::Answer;
+use crate::Day14::part1::evaluate;
+use crate::Day14::Reaction;
+
+const ORE_AVAILABLE: i64 = 1_000_000_000_000;
+
+pub fn solve(reactions: &[Reaction]) -> Answer {
+    // Leftovers only make more FUEL than making it one at a time
+    let mut lo = ORE_AVAILABLE / evaluate(1, reactions);
+    let mut hi = lo.max(1) * 2;
+    while evaluate(hi, reactions) <= ORE_AVAILABLE {
+        lo = hi;
+        hi *= 2;
     }
 
-    let mut fuel_to_produce: i64 = 1000000000000 / 362713; // We know from part 1 we can produce at least that much
-    let mut inc_ratio = fuel_to_produce / 2;
-
-    loop {
-        let res = evaluate(fuel_to_produce, &reactions);
-
-        // println!("Producing {} FUEL require {} ORE", fuel_to_produce, res);
-
-        if res > 1000000000000 {
-            fuel_to_produce -= inc_ratio;
-            inc_ratio /= 2;
-            fuel_to_produce += inc_ratio;
+    // `lo` FUEL can be produced, `hi` can't
+    while hi - lo > 1 {
+        let mid = lo + (hi - lo) / 2;
+        if evaluate(mid, reactions) <= ORE_AVAILABLE {
+            lo = mid;
         } else {
-            // Make sure we're at the edge
-            let new_res = evaluate(fuel_to_produce + 1, &reactions);
-            if new_res > 1000000000000 {
-                break;
-            }
-
-            fuel_to_produce += inc_ratio;
+            hi = mid;
         }
     }
 
-    println!("We can produce {} FUEL", fuel_to_produce);
+    lo.into()
 }
@@ -0,0 +1,96 @@
+use super::Day14;
+use crate::solution::{Answer, Solution};
+use crate::testing::{part1, part2};
+
+const SIMPLE: &str = "10 ORE => 10 A
+1 ORE => 1 B
+7 A, 1 B => 1 C
+7 A, 1 C => 1 D
+7 A, 1 D => 1 E
+7 A, 1 E => 1 FUEL
+";
+
+const LEFTOVERS: &str = "9 ORE => 2 A
+8 ORE => 3 B
+7 ORE => 5 C
+3 A, 4 B => 1 AB
+5 B, 7 C => 1 BC
+4 C, 1 A => 1 CA
+2 AB, 3 BC, 4 CA => 1 FUEL
+";
+
+const LARGE: &str = "157 ORE => 5 NZVS
+165 ORE => 6 DCFZ
+44 XJWVT, 5 KHKGT, 1 QDVJ, 29 NZVS, 9 GPVTF, 48 HKGWZ => 1 FUEL
+12 HKGWZ, 1 GPVTF, 8 PSHF => 9 QDVJ
+179 ORE => 7 PSHF
+177 ORE => 5 HKGWZ
+7 DCFZ, 7 PSHF => 2 XJWVT
+165 ORE => 2 GPVTF
+3 DCFZ, 7 NZVS, 5 HKGWZ, 10 PSHF => 8 KHKGT
+";
+
+const LARGEST: &str = "171 ORE => 8 CNZTR
+7 ZLQW, 3 BMBT, 9 XCVML, 26 XMNCP, 1 WPTQ, 2 MZWV, 1 RJRHP => 4 PLWSL
+114 ORE => 4 BHXH
+14 VRPVC => 6 BMBT
+6 BHXH, 18 KTJDG, 12 WPTQ, 7 PLWSL, 31 FHTLT, 37 ZDVW => 1 FUEL
+6 WPTQ, 2 BMBT, 8 ZLQW, 18 KTJDG, 1 XMNCP, 6 MZWV, 1 RJRHP => 6 FHTLT
+15 XDBXC, 2 LTCX, 1 VRPVC => 6 ZLQW
+13 WPTQ, 10 LTCX, 3 RJRHP, 14 XMNCP, 2 MZWV, 1 ZLQW => 1 ZDVW
+5 BMBT => 4 WPTQ
+189 ORE => 9 KTJDG
+1 MZWV, 17 XDBXC, 3 XCVML => 2 XMNCP
+12 VRPVC, 27 CNZTR => 2 XDBXC
+15 KTJDG, 12 BHXH => 5 XCVML
+3 BHXH, 2 VRPVC => 7 MZWV
+121 ORE => 7 VRPVC
+7 XCVML => 6 RJRHP
+5 BHXH, 4 VRPVC => 5 LTCX
+";
+
+const LARGER: &str = "2 VPVL, 7 FWMGM, 2 CXFTF, 11 MNCFX => 1 STKFG
+17 NVRVD, 3 JNWZP => 8 VPVL
+53 STKFG, 6 MNCFX, 46 VJHF, 81 HVMC, 68 CXFTF, 25 GNMV => 1 FUEL
+22 VJHF, 37 MNCFX => 5 FWMGM
+139 ORE => 4 NVRVD
+144 ORE => 7 JNWZP
+5 MNCFX, 7 RFSQX, 2 FWMGM, 2 VPVL, 19 CXFTF => 3 HVMC
+5 VJHF, 7 MNCFX, 9 VPVL, 37 CXFTF => 6 GNMV
+145 ORE => 6 MNCFX
+1 NVRVD => 8 CXFTF
+1 VJHF, 6 MNCFX => 4 RFSQX
+176 ORE => 6 VJHF
+";
+
+#[test]
+fn ore_for_one_fuel() {
+    assert_eq!(part1::<Day14>(SIMPLE), Answer::Number(31));
+    assert_eq!(part1::<Day14>(LEFTOVERS), Answer::Number(165));
+    assert_eq!(part1::<Day14>(LARGE), Answer::Number(13312));
+    assert_eq!(part1::<Day14>(LARGER), Answer::Number(180697));
+    assert_eq!(part1::<Day14>(LARGEST), Answer::Number(2210736));
+}
+
+#[test]
+fn fuel_from_a_trillion_ore() {
+    assert_eq!(part2::<Day14>(LARGE), Answer::Number(82892753));
+    assert_eq!(part2::<Day14>(LARGER), Answer::Number(5586022));
+    assert_eq!(part2::<Day14>(LARGEST), Answer::Number(460664));
+    // A single reaction making FUEL straight from ORE, with nothing left over
+    assert_eq!(part2::<Day14>("1000 ORE => 3 FUEL\n"), Answer::Number(3_000_000_000));
+    assert_eq!(part2::<Day14>("2000000000000 ORE => 1 FUEL\n"), Answer::Number(0));
+}
+
+#[test]
+fn reactions_must_make_fuel_from_ore() {
+    let error = |input: &str| Day14::parse(input).err().map(|e| (e.line, e.column, e.message));
+    assert_eq!(error("1 ORE => 1 A\n2 A, 3 B => 1 FUEL\n"), Some((2, 6, String::from("no reaction produces B"))));
+    assert_eq!(error("1 ORE => 1 A\n"), Some((1, 1, String::from("no reaction produces FUEL"))));
+    assert_eq!(error("1 ORE => 1 A\n2 ORE => 1 A\n1 A => 1 FUEL\n"), Some((2, 10, String::from("A is produced by 2 reactions"))));
+    assert_eq!(error("1 A => 1 ORE\n"), Some((1, 8, String::from("ORE can't be produced"))));
+    assert_eq!(error("1 ORE => 0 A\n"), Some((1, 10, String::from("the quantity of A must be positive"))));
+    // Without ORE the reactions can only go round in circles
+    assert_eq!(error("1 B => 1 A\n1 A => 1 B\n1 A => 1 FUEL\n"), Some((2, 1, String::from("A is part of a reaction cycle"))));
+    assert_eq!(error("2 FUEL => 1 FUEL\n"), Some((1, 1, String::from("FUEL is part of a reaction cycle"))));
+}
@@ -1,2 +1,44 @@
 pub mod part1;
 pub mod part2;
+#[cfg(test)]
+mod tests;
+
+use crate::animation::Recorder;
+use crate::export::Raster;
+use crate::intcode::session::Sessions;
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution, SolveError};
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = Vec<i64>;
+
+    fn parse(input: &str) -> Result<Vec<i64>, ParseError> {
+        parse::intcode(input)
+    }
+
+    fn part1(input: &Vec<i64>) -> Result<Answer, SolveError> {
+        part1::solve(input, &mut Sessions::disabled())
+    }
+
+    fn part2(input: &Vec<i64>) -> Result<Answer, SolveError> {
+        part2::solve(input, &mut Sessions::disabled())
+    }
+
+    fn solve_part(input: &Vec<i64>, part: usize, sessions: &mut Sessions) -> Result<Answer, SolveError> {
+        match part {
+            1 => part1::solve(input, sessions),
+            _ => part2::solve(input, sessions),
+        }
+    }
+
+    fn picture(input: &Vec<i64>) -> Result<Option<Raster>, SolveError> {
+        Ok(Some(part1::picture(input)?))
+    }
+
+    fn animate(input: &Vec<i64>, recorder: &mut Recorder) -> Result<bool, SolveError> {
+        part1::animate(input, recorder)?;
+        Ok(true)
+    }
+}
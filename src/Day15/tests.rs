@@ -0,0 +1,73 @@
+use super::part1::{Map, TileType};
+use super::{part1, part2, Day15};
+use crate::grid::SparseGrid;
+use crate::intcode::session::{self, Sessions};
+use crate::solution;
+use std::fs;
+
+// Map drawn with `#` for walls, `.` for open tiles, `D` for the droid and `O` for the oxygen system
+fn map(text: &str) -> Map {
+    let mut map = SparseGrid::new(TileType::Unexplored);
+    for (y, line) in text.lines().enumerate() {
+        for (x, c) in line.chars().enumerate().filter(|&(_, c)| c != ' ') {
+            let tile = match c {
+                '#' => TileType::Wall,
+                'D' => TileType::InitialPos,
+                'O' => TileType::OxygenSys,
+                _ => TileType::Empty,
+            };
+            map.set((x as i64, y as i64), tile);
+        }
+    }
+    map
+}
+
+#[test]
+fn steps_around_the_walls() {
+    let text = "#####
+#D..#
+###.#
+#O..#
+#####";
+    assert_eq!(part1::steps_to_oxygen(&map(text)).unwrap(), 6);
+}
+
+#[test]
+fn shortest_of_two_ways() {
+    let text = "#######
+#D....#
+#.###.#
+#.....#
+###O###
+  ###";
+    assert_eq!(part1::steps_to_oxygen(&map(text)).unwrap(), 5);
+}
+
+#[test]
+fn oxygen_spreading_in_the_example() {
+    let text = " ##
+#..##
+#.#..#
+#.O.#
+ ###";
+    assert_eq!(part2::minutes_to_fill(&map(text)).unwrap(), 4);
+}
+
+#[test]
+fn recorded_exploration_replays() {
+    let input = fs::read_to_string("input_day15.txt").unwrap();
+    let mut sessions = Sessions::new();
+    let result = solution::solve::<Day15>(&input, &[1, 2], &mut sessions).unwrap();
+
+    for part in result.parts {
+        assert!(part.answer.is_ok());
+        assert_eq!(part.recordings.len(), 1);
+        let recording = &part.recordings[0];
+        // Every move of the droid is an input
+        assert!(!recording.session.inputs().is_empty());
+        assert_eq!(session::replay(&recording.image, &recording.session), Ok(recording.session.entries.len()));
+    }
+
+    let result = solution::solve::<Day15>(&input, &[1], &mut Sessions::disabled()).unwrap();
+    assert!(result.parts[0].recordings.is_empty());
+}
@@ -1,2 +1,50 @@
 pub mod part1;
 pub mod part2;
+#[cfg(test)]
+mod tests;
+
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution, SolveError};
+
+pub const INPUT: &str = "59776034095811644545367793179989602140948714406234694972894485066523525742503986771912019032922788494900655855458086979764617375580802558963587025784918882219610831940992399201782385674223284411499237619800193879768668210162176394607502218602633153772062973149533650562554942574593878073238232563649673858167635378695190356159796342204759393156294658366279922734213385144895116649768185966866202413314939692174223210484933678866478944104978890019728562001417746656699281992028356004888860103805472866615243544781377748654471750560830099048747570925902575765054898899512303917159138097375338444610809891667094051108359134017128028174230720398965960712";
+
+pub struct Signal {
+    pub digits: Vec<i64>,
+    // Position of the message in the signal repeated 10000 times, checked when parsing but only
+    // needed by part 2 so the examples of part 1 still parse
+    pub offset: Result<usize, ParseError>,
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = Signal;
+
+    fn parse(input: &str) -> Result<Signal, ParseError> {
+        let digits = parse::digits(input)?;
+        let line = parse::single_line(input)?;
+        if digits.len() < 8 {
+            return Err(line.error("signal is shorter than 8 digits"));
+        }
+
+        // Part 2 only works when the message is in the second half of the real signal
+        let offset = digits[0..7].iter().fold(0, |offset, digit| offset * 10 + *digit as usize);
+        let length = digits.len() * 10000;
+        let offset = if offset < length / 2 || offset + 8 > length {
+            Err(line.error(format!("message offset {} is not in the second half of the {} digits of the real signal", offset, length)))
+        } else {
+            Ok(offset)
+        };
+
+        Ok(Signal { digits, offset })
+    }
+
+    fn part1(input: &Signal) -> Result<Answer, SolveError> {
+        Ok(part1::solve(&input.digits))
+    }
+
+    fn part2(input: &Signal) -> Result<Answer, SolveError> {
+        let offset = input.offset.clone()?;
+        Ok(part2::solve(&input.digits, offset))
+    }
+}
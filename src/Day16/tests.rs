@@ -0,0 +1,33 @@
+use super::Day16;
+use crate::solution::{Answer, Solution};
+use crate::testing::{part1, part2};
+
+fn text(message: &str) -> Answer {
+    Answer::Text(String::from(message))
+}
+
+#[test]
+fn first_digits_after_a_hundred_phases() {
+    assert_eq!(part1::<Day16>("80871224585914546619083218645595"), text("24176176"));
+    assert_eq!(part1::<Day16>("19617804207202209144916044189917"), text("73745418"));
+    assert_eq!(part1::<Day16>("69317163492948606335995924319873"), text("52432133"));
+}
+
+#[test]
+fn message_of_the_real_signal() {
+    assert_eq!(part2::<Day16>("03036732577212944063491565474664"), text("84462026"));
+    assert_eq!(part2::<Day16>("02935109699940807407585447034323"), text("78725270"));
+    assert_eq!(part2::<Day16>("03081770884921959731165446850517"), text("53553731"));
+}
+
+#[test]
+fn message_offset_must_be_in_the_second_half() {
+    // 0303673 of 320000 digits is fine, 0000001 is in the first half and 9999999 past the end
+    assert!(Day16::parse("03036732577212944063491565474664").unwrap().offset.is_ok());
+    let first_half = Day16::parse("00000012577212944063491565474664").unwrap();
+    assert_eq!(first_half.offset.as_ref().unwrap_err().column, 1);
+    assert!(Day16::parse("99999992577212944063491565474664").unwrap().offset.is_err());
+
+    let error = Day16::part2(&first_half).unwrap_err();
+    assert!(error.to_string().contains("message offset 1 is not in the second half"), "{}", error);
+}
@@ -1,2 +1,36 @@
 pub mod part1;
 pub mod part2;
+#[cfg(test)]
+mod tests;
+
+use crate::animation::Recorder;
+use crate::export::Raster;
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution, SolveError};
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = Vec<i64>;
+
+    fn parse(input: &str) -> Result<Vec<i64>, ParseError> {
+        parse::intcode(input)
+    }
+
+    fn part1(input: &Vec<i64>) -> Result<Answer, SolveError> {
+        part1::solve(input)
+    }
+
+    fn part2(input: &Vec<i64>) -> Result<Answer, SolveError> {
+        part2::solve(input)
+    }
+
+    fn picture(input: &Vec<i64>) -> Result<Option<Raster>, SolveError> {
+        Ok(Some(part1::picture(input)?))
+    }
+
+    fn animate(input: &Vec<i64>, recorder: &mut Recorder) -> Result<bool, SolveError> {
+        part2::run(input, recorder)?;
+        Ok(true)
+    }
+}
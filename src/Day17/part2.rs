@@ -1,217 +1,10 @@
-use std::collections::VecDeque;
-use std::fs::File;
-use std::io::Read;
-
-#[repr(u8)]
-#[derive(PartialEq)]
-enum ParamType {
-    PositionMode = 0,
-    ImmediateMode = 1,
-    RelativeMode = 2,
-}
-
-fn get_param_type(mode: i64) -> ParamType {
-    match mode {
-        0 => ParamType::PositionMode,
-        1 => ParamType::ImmediateMode,
-        2 => ParamType::RelativeMode,
-        u => panic!("Unexpected parameter type: {}", u),
-    }
-}
-
-struct Param {
-    mode: ParamType,
-    value: i64,
-    relative_base: i64,
-}
-
-impl Param {
-    fn new(vec: &Vec<i64>, index: usize, param_index: usize, relative_base: i64) -> Param {
-        let mode = get_param_type((vec[index] / 10i64.pow((param_index + 1) as u32)) % 10);
-        let value = vec[index + param_index];
-        Param { mode, value, relative_base }
-    }
-
-    fn get_value(&self, vec: &Vec<i64>) -> i64 {
-        match self.mode {
-            ParamType::PositionMode => vec[self.value as usize],
-            ParamType::ImmediateMode => self.value,
-            ParamType::RelativeMode => vec[(self.value + self.relative_base) as usize],
-        }
-    }
-
-    fn set_value(&self, vec: &mut Vec<i64>, value: i64) {
-        match self.mode {
-            ParamType::PositionMode => vec[self.value as usize] = value,
-            ParamType::ImmediateMode => panic!("set_value called with a parameter in ImmediateMode!"),
-            ParamType::RelativeMode => vec[(self.value + self.relative_base) as usize] = value,
-        }
-    }
-}
-
-#[derive(Clone)]
-struct Program {
-    state: Vec<i64>,
-    current_op: usize,
-    finished: bool,
-    input: VecDeque<i64>,
-    output: VecDeque<i64>,
-    relative_base: i64,
-}
-
-impl Program {
-    fn new(program: &Vec<i64>, input: VecDeque<i64>) -> Program {
-        let mut state = vec![0; 10000];
-        state.as_mut_slice()[0..program.len()].copy_from_slice(program.as_slice());
-
-        Program {
-            state,
-            current_op: 0,
-            finished: false,
-            input,
-            output: VecDeque::new(),
-            relative_base: 0,
-        }
-    }
-
-    fn _is_finished(&self) -> bool {
-        self.finished
-    }
-
-    fn get_params(&self, num_params: usize) -> Vec<Param> {
-        let mut params = Vec::new();
-
-        for i in 1..num_params + 1 {
-            params.push(Param::new(&self.state, self.current_op, i, self.relative_base));
-        }
-
-        params
-    }
-
-    fn op_add(&mut self) {
-        let params = self.get_params(3);
-
-        let sum = params[0].get_value(&self.state) + params[1].get_value(&self.state);
-        params[2].set_value(&mut self.state, sum);
-
-        self.current_op += 4;
-    }
-
-    fn op_mul(&mut self) {
-        let params = self.get_params(3);
-
-        let product = params[0].get_value(&self.state) * params[1].get_value(&self.state);
-        params[2].set_value(&mut self.state, product);
-
-        self.current_op += 4;
-    }
-
-    fn op_input(&mut self) -> bool {
-        let params = self.get_params(1);
-
-        let input = self.input.pop_front();
-        if input.is_none() {
-            return false;
-        }
-
-        params[0].set_value(&mut self.state, input.unwrap());
-        self.current_op += 2;
-        return true;
-    }
-
-    fn op_output(&mut self) {
-        let params = self.get_params(1);
-        let value = params[0].get_value(&self.state);
-        self.output.push_back(value);
-        self.current_op += 2;
-
-        // println!("Output: {}", value);
-    }
-
-    fn op_jump_if_true(&mut self) {
-        let params = self.get_params(2);
-
-        if params[0].get_value(&self.state) != 0 {
-            self.current_op = params[1].get_value(&self.state) as usize;
-        } else {
-            self.current_op += 3;
-        }
-    }
-
-    fn op_jump_if_false(&mut self) {
-        let params = self.get_params(2);
-
-        if params[0].get_value(&self.state) == 0 {
-            self.current_op = params[1].get_value(&self.state) as usize;
-        } else {
-            self.current_op += 3;
-        }
-    }
-
-    fn op_lessthan(&mut self) {
-        let params = self.get_params(3);
-
-        let to_store;
-        if params[0].get_value(&self.state) < params[1].get_value(&self.state) {
-            to_store = 1;
-        } else {
-            to_store = 0;
-        }
-        params[2].set_value(&mut self.state, to_store);
-        self.current_op += 4;
-    }
-
-    fn op_equal(&mut self) {
-        let params = self.get_params(3);
-
-        let to_store;
-        if params[0].get_value(&self.state) == params[1].get_value(&self.state) {
-            to_store = 1;
-        } else {
-            to_store = 0;
-        }
-        params[2].set_value(&mut self.state, to_store);
-        self.current_op += 4;
-    }
-
-    fn op_adjust_relative_base(&mut self) {
-        let params = self.get_params(1);
-
-        self.relative_base += params[0].get_value(&self.state);
-        self.current_op += 2;
-    }
-
-    fn run_program(&mut self, input: Vec<i64>) -> &VecDeque<i64> {
-        self.output.clear();
-
-        self.input.extend(input);
-
-        while self.current_op < self.state.len() {
-            match self.state[self.current_op] % 100 {
-                1 => self.op_add(),
-                2 => self.op_mul(),
-                3 => {
-                    if !self.op_input() {
-                        break;
-                    }
-                }
-                4 => self.op_output(),
-                5 => self.op_jump_if_true(),
-                6 => self.op_jump_if_false(),
-                7 => self.op_lessthan(),
-                8 => self.op_equal(),
-                9 => self.op_adjust_relative_base(),
-                99 => {
-                    self.finished = true;
-                    break;
-                }
-                _ => panic!("Invalid opcode!"),
-            }
-        }
-
-        &self.output
-    }
-}
+use crate::animation::{Frame, Recorder};
+use crate::export::{BLACK, WHITE};
+use crate::grid::Grid;
+use crate::intcode::vm::Program;
+use crate::solution::{self, Answer, SolveError};
+use crate::Day17::part1;
+use std::fmt;
 
 #[repr(u8)]
 #[derive(Clone, Copy, PartialEq)]
@@ -272,29 +65,7 @@ impl Direction {
     }
 }
 
-struct Map {
-    map: Vec<Vec<TileType>>,
-    width: i64,
-    height: i64,
-}
-impl Map {
-    fn new(map: Vec<Vec<TileType>>) -> Map {
-        let height = map.len() as i64;
-        let width = map[0].len() as i64;
-        Map { map, width, height }
-    }
-    fn is_scaffold(&self, x: i64, y: i64) -> bool {
-        if x < 0 || y < 0 || x >= self.width || y >= self.height {
-            return false;
-        }
-
-        if self.map[y as usize][x as usize] == TileType::Scaffold {
-            true
-        } else {
-            false
-        }
-    }
-}
+type Map = Grid<TileType>;
 
 struct Robot {
     x: i64,
@@ -318,14 +89,10 @@ impl Instruction {
             steps,
         }
     }
-    fn to_string(&self) -> String {
-        let mut res_str = String::new();
-        res_str.push(self.dir_char);
-        res_str += ",";
-        res_str += &self.steps.to_string();
-        res_str += ",";
-
-        res_str
+}
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{},{},", self.dir_char, self.steps)
     }
 }
 
@@ -333,7 +100,7 @@ fn test_dir(map: &Map, robot: &Robot, dir: Direction) -> i64 {
     let mut num_scaffold = 0;
     let mut x = robot.x + dir.get_x();
     let mut y = robot.y + dir.get_y();
-    while map.is_scaffold(x, y) {
+    while map.get((x, y)) == Some(&TileType::Scaffold) {
         num_scaffold += 1;
         x += dir.get_x();
         y += dir.get_y();
@@ -364,88 +131,132 @@ fn get_next_instruction(map: &Map, robot: &mut Robot) -> Option<Instruction> {
     None
 }
 
-pub fn run_puzzle() {
-    let mut file = File::open("input_day17.txt").expect("Failed to open input_day17.txt");
-    let mut ops_string = String::new();
-    file.read_to_string(&mut ops_string).unwrap();
-
-    let vec: Vec<i64> = ops_string.split(',').map(|text| text.trim().parse().unwrap()).collect();
-
-    let mut program = Program::new(&vec, VecDeque::new());
-    let mut program_clone = program.clone();
-
-    let map_output = program.run_program(Vec::new());
-
-    let map_string: String = map_output.iter().map(|v| (*v as u8) as char).collect();
-    println!("{}", map_string);
-
-    let mut map_vec: Vec<Vec<TileType>> = Vec::new();
-
+// Instructions walking the whole scaffold, as `R,8,L,6,` ...
+pub fn movements(camera: &Grid<char>, recorder: &mut Recorder) -> String {
     let mut robot = Robot::new(0, 0, Direction::North);
-
-    let mut y = 0;
-    for l in map_string.trim().lines() {
-        let mut new_line = Vec::new();
-        let mut x = 0;
-        for c in l.chars() {
-            let tile = match c {
-                '.' => TileType::Empty,
-                '#' => TileType::Scaffold,
-                '^' => {
-                    robot = Robot::new(x, y, Direction::North);
-                    TileType::Scaffold
-                }
-                '<' => {
-                    robot = Robot::new(x, y, Direction::West);
-                    TileType::Scaffold
-                }
-                '>' => {
-                    robot = Robot::new(x, y, Direction::East);
-                    TileType::Scaffold
-                }
-                'v' => {
-                    robot = Robot::new(x, y, Direction::South);
-                    TileType::Scaffold
-                }
-                o => panic!("Unhandled Tile: {}", o),
-            };
-            new_line.push(tile);
-            x += 1;
+    for ((x, y), c) in camera.iter() {
+        match c {
+            '^' => robot = Robot::new(x, y, Direction::North),
+            '<' => robot = Robot::new(x, y, Direction::West),
+            '>' => robot = Robot::new(x, y, Direction::East),
+            'v' => robot = Robot::new(x, y, Direction::South),
+            _ => {}
         }
-        map_vec.push(new_line);
-        y += 1;
     }
 
-    let map = Map::new(map_vec);
+    let map = camera.map(|c| if *c == '.' { TileType::Empty } else { TileType::Scaffold });
+
+    // Scaffold in white, turning orange once walked over
+    let mut trail = map.map(|tile| if *tile == TileType::Scaffold { WHITE } else { BLACK });
 
     let mut all_insts = Vec::new();
+    let mut position = (robot.x, robot.y);
     while let Some(inst) = get_next_instruction(&map, &mut robot) {
         all_insts.push(inst);
+
+        while position != (robot.x, robot.y) {
+            position = (position.0 + robot.dir.get_x(), position.1 + robot.dir.get_y());
+            trail[position] = [220, 120, 40];
+            recorder.capture(|| Frame::grid(&trail, |c| *c).mark(position, [220, 40, 40]));
+        }
     }
+    recorder.finish(|| Frame::grid(&trail, |c| *c));
 
     let mut inst_str = String::new();
     for i in &all_insts {
         inst_str += &i.to_string();
     }
+    inst_str
+}
 
-    println!("Instructions: {}", inst_str);
+// Longest line of the movement routine the robot accepts
+const MAX_LINE: usize = 20;
 
-    // Search for 3 non overlapping patterns <= 20 bytes that make up the instruction list
+// Splits the remaining moves between the functions, adding functions starting at the first move
+// not covered by the existing ones
+fn split<'a>(moves: &'a [&'a str], functions: &mut Vec<&'a [&'a str]>, main: &mut Vec<usize>) -> bool {
+    if moves.is_empty() {
+        return true;
+    }
+    // Every call takes a letter and a comma
+    if main.len() * 2 + 1 > MAX_LINE {
+        return false;
+    }
 
-    // Shamefully solved by hand in a text editor :`D
-    let result_str = "A,A,B,C,B,C,B,C,B,A\nL,10,L,8,R,8,L,8,R,6\nR,6,R,8,R,8\nR,6,R,6,L,8,L,10\nn\n";
+    for index in 0..functions.len() {
+        let function = functions[index];
+        if moves.starts_with(function) {
+            main.push(index);
+            if split(&moves[function.len()..], functions, main) {
+                return true;
+            }
+            main.pop();
+        }
+    }
 
-    let input: Vec<i64> = result_str.chars().map(|c| c as i64).collect();
+    if functions.len() < 3 {
+        for len in 1..=moves.len() {
+            if moves[..len].join(",").len() > MAX_LINE {
+                break;
+            }
+            functions.push(&moves[..len]);
+            main.push(functions.len() - 1);
+            if split(&moves[len..], functions, main) {
+                return true;
+            }
+            main.pop();
+            functions.pop();
+        }
+    }
 
-    program_clone.state[0] = 2;
-    let output = program_clone.run_program(input);
+    false
+}
 
-    let map_string: String = output.iter().map(|v| (*v as u8) as char).collect();
-    println!("{}", map_string);
+// Main routine and the 3 functions walking the path, one per line, None when the path can't be
+// split into lines of at most 20 characters
+pub fn routine(path: &str) -> Option<String> {
+    let tokens: Vec<&str> = path.trim_end_matches(',').split(',').collect();
+    let moves: Vec<String> = tokens.chunks(2).map(|pair| pair.join(",")).collect();
+    let moves: Vec<&str> = moves.iter().map(|m| m.as_str()).collect();
 
-    for v in output {
-        if *v > 255 {
-            println!("Output: {}", v);
-        }
+    let (mut functions, mut main) = (Vec::new(), Vec::new());
+    if !split(&moves, &mut functions, &mut main) {
+        return None;
     }
+    // The robot always reads 3 functions
+    while functions.len() < 3 {
+        functions.push(functions[0]);
+    }
+
+    let main: Vec<String> = main.iter().map(|&index| ((b'A' + index as u8) as char).to_string()).collect();
+    let mut routine = main.join(",") + "\n";
+    for function in functions {
+        routine += &function.join(",");
+        routine += "\n";
+    }
+    Some(routine)
+}
+
+// Walks the robot along the scaffold and returns the dust it collected
+pub fn run(vec: &[i64], recorder: &mut Recorder) -> Result<i64, SolveError> {
+    let camera = part1::camera(vec)?;
+
+    let path = movements(&camera, recorder);
+    let routine = routine(&path).ok_or_else(|| SolveError::Failed(String::from("The path can't be split into 3 movement functions of at most 20 characters")))?;
+
+    let mut image = vec.to_vec();
+    image[0] = 2;
+
+    let mut program = Program::new(&image);
+    // Followed by the answer to the continuous video feed question
+    program.extend_input(routine.chars().chain("n\n".chars()).map(|c| c as i64));
+
+    // Everything but the dust amount is ASCII
+    let dust = solution::run_to_halt(&mut program)?.into_iter().find(|v| *v > 255).ok_or_else(|| SolveError::Failed(String::from("No dust collected")))?;
+
+    Ok(dust)
+}
+
+pub fn solve(vec: &[i64]) -> Result<Answer, SolveError> {
+    Ok(run(vec, &mut Recorder::disabled())?.into())
 }
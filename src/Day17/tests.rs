@@ -0,0 +1,66 @@
+use super::{part1, part2};
+use crate::animation::Recorder;
+use crate::grid::Grid;
+
+fn camera(view: &str) -> Grid<char> {
+    Grid::parse(view, |c| if "#.^<>v".contains(c) { Some(c) } else { None }).unwrap_or_else(|e| panic!("{}", e))
+}
+
+#[test]
+fn alignment_of_the_intersections() {
+    let view = "..#..........
+..#..........
+#######...###
+#.#...#...#.#
+#############
+..#...#...#..
+..#####...^..
+";
+    assert_eq!(part1::alignment_sum(&camera(view)), 76);
+}
+
+#[test]
+fn movements_along_the_scaffold() {
+    let view = "#######...#####
+#.....#...#...#
+#.....#...#...#
+......#...#...#
+......#...###.#
+......#.....#.#
+^########...#.#
+......#.#...#.#
+......#########
+........#...#..
+....#########..
+....#...#......
+....#...#......
+....#...#......
+....#####......
+";
+    let path = "R,8,R,8,R,4,R,4,R,8,L,6,L,2,R,4,R,4,R,8,R,8,R,8,L,6,L,2,";
+    assert_eq!(part2::movements(&camera(view), &mut Recorder::disabled()), path);
+}
+
+// Path walked by the main routine and the functions
+fn expand(routine: &str) -> String {
+    let lines: Vec<&str> = routine.lines().collect();
+    lines[0].split(',').map(|call| format!("{},", lines[1 + (call.as_bytes()[0] - b'A') as usize])).collect()
+}
+
+#[test]
+fn routine_walks_the_path() {
+    let path = "R,8,R,8,R,4,R,4,R,8,L,6,L,2,R,4,R,4,R,8,R,8,R,8,L,6,L,2,";
+    let routine = part2::routine(path).unwrap();
+    assert_eq!(routine.lines().count(), 4);
+    assert!(routine.lines().all(|line| line.len() <= 20), "{}", routine);
+    assert_eq!(expand(&routine), path);
+
+    // The first function is repeated to fill the 3 the robot expects
+    assert_eq!(part2::routine("L,4,R,2,L,4,R,2,").unwrap(), "A,B,A,B\nL,4\nR,2\nL,4\n");
+}
+
+#[test]
+fn routine_needs_short_functions() {
+    let path: String = (1..=20).map(|steps| format!("R,{},", steps)).collect();
+    assert_eq!(part2::routine(&path), None);
+}
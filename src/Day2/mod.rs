@@ -1,2 +1,31 @@
 pub mod part1;
 pub mod part2;
+#[cfg(test)]
+mod tests;
+
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution, SolveError};
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<i64>;
+
+    // The noun and the verb are written at addresses 1 and 2
+    fn parse(input: &str) -> Result<Vec<i64>, ParseError> {
+        let program = parse::intcode(input)?;
+        if program.len() < 3 {
+            return Err(parse::whole(input).error("the program is too short for a noun and a verb"));
+        }
+
+        Ok(program)
+    }
+
+    fn part1(input: &Vec<i64>) -> Result<Answer, SolveError> {
+        part1::solve(input)
+    }
+
+    fn part2(input: &Vec<i64>) -> Result<Answer, SolveError> {
+        part2::solve(input)
+    }
+}
@@ -0,0 +1,19 @@
+use super::part1::run_program;
+
+fn run(program: &[i64]) -> Vec<i64> {
+    run_program(program).unwrap_or_else(|e| panic!("{}", e))
+}
+
+#[test]
+fn programs_of_the_examples() {
+    assert_eq!(run(&[1, 9, 10, 3, 2, 3, 11, 0, 99, 30, 40, 50]), vec![3500, 9, 10, 70, 2, 3, 11, 0, 99, 30, 40, 50]);
+    assert_eq!(run(&[1, 0, 0, 0, 99]), vec![2, 0, 0, 0, 99]);
+    assert_eq!(run(&[2, 3, 0, 3, 99]), vec![2, 3, 0, 6, 99]);
+    assert_eq!(run(&[2, 4, 4, 5, 99, 0]), vec![2, 4, 4, 5, 99, 9801]);
+    assert_eq!(run(&[1, 1, 1, 4, 99, 5, 6, 0, 99]), vec![30, 1, 1, 4, 2, 5, 6, 0, 99]);
+}
+
+#[test]
+fn invalid_opcode_is_an_error() {
+    assert!(run_program(&[1, 0, 0, 0, 42]).is_err());
+}
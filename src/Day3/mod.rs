@@ -1,2 +1,30 @@
 pub mod part1;
 pub mod part2;
+#[cfg(test)]
+mod tests;
+
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution, SolveError};
+
+// Moves of a wire as direction (L, R, U or D) and distance
+pub type Path = Vec<(char, i64)>;
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = Vec<Path>;
+
+    fn parse(input: &str) -> Result<Vec<Path>, ParseError> {
+        parse::lines(input)
+            .map(|line| line.split(",").into_iter().map(|op| parse::direction_distance(op, "LRUD")).collect())
+            .collect()
+    }
+
+    fn part1(input: &Vec<Path>) -> Result<Answer, SolveError> {
+        part1::solve(input)
+    }
+
+    fn part2(input: &Vec<Path>) -> Result<Answer, SolveError> {
+        part2::solve(input)
+    }
+}
@@ -1,52 +1,47 @@
+use crate::solution::{Answer, SolveError};
+use crate::Day3::Path;
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-
-pub fn run_puzzle() {
-    let file = File::open("input_day3.txt").expect("Failed to open input_day3.txt");
-    let br = BufReader::new(file);
 
+pub fn solve(paths: &[Path]) -> Result<Answer, SolveError> {
     let mut wires: HashMap<(i64, i64), (u64, u64)> = HashMap::new();
     let mut curwire = 1;
 
-    for line in br.lines() {
+    for path in paths {
         let mut curpos = (0, 0);
         let mut steps = 0;
 
         let mut check_wire = |wire: &mut (u64, u64)| {
                         if (wire.0 & curwire) == 0 {
-                            (*wire).0 |= curwire;
-                            (*wire).1 += steps;
+                            wire.0 |= curwire;
+                            wire.1 += steps;
                         }
                         steps += 1;
         };
 
-        for op in line.unwrap().split(',') {
-            let diff = i64::from_str_radix(&op[1..], 10).unwrap();
-
-            match op.get(0..1).unwrap() {
-                "L" => {
+        for &(direction, diff) in path {
+            match direction {
+                'L' => {
                     for x in (((curpos.0 + 1) - diff)..(curpos.0 + 1)).rev() {
                         let wire = wires.entry((x, curpos.1)).or_insert((0, 0));
                         check_wire(wire);
                     }
                     curpos.0 -= diff;
                 }
-                "R" => {
+                'R' => {
                     for x in curpos.0..curpos.0 + diff {
                         let wire = wires.entry((x, curpos.1)).or_insert((0, 0));
                         check_wire(wire);
                     }
                     curpos.0 += diff;
                 }
-                "U" => {
+                'U' => {
                     for y in (((curpos.1 + 1) - diff)..(curpos.1 + 1)).rev() {
                         let wire = wires.entry((curpos.0, y)).or_insert((0, 0));
                         check_wire(wire);
                     }
                     curpos.1 -= diff;
                 }
-                "D" => {
+                'D' => {
                     for y in curpos.1..curpos.1 + diff {
                         let wire = wires.entry((curpos.0, y)).or_insert((0, 0));
                         check_wire(wire);
@@ -59,15 +54,18 @@ pub fn run_puzzle() {
         curwire <<= 1;
     }
 
-    let mut curdist: u64 = std::u64::MAX;
+    let mut curdist: u64 = u64::MAX;
 
     for point in wires {
-        if (point.1).0 == 3 {
-            if (point.1).1 != 0 && (point.1).1 < curdist {
+        if (point.1).0 == 3
+            && (point.1).1 != 0 && (point.1).1 < curdist {
                 curdist = (point.1).1;
             }
-        }
     }
 
-    println!("Result: {}", curdist);
+    if curdist == u64::MAX {
+        return Err(SolveError::Failed(String::from("The wires never cross")));
+    }
+
+    Ok(curdist.into())
 }
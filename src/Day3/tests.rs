@@ -0,0 +1,23 @@
+use super::Day3;
+use crate::solution::Answer;
+use crate::testing::{part1, part2};
+
+const EXAMPLES: &[(&str, i64, i64)] = &[
+    ("R8,U5,L5,D3\nU7,R6,D4,L4\n", 6, 30),
+    ("R75,D30,R83,U83,L12,D49,R71,U7,L72\nU62,R66,U55,R34,D71,R55,D58,R83\n", 159, 610),
+    ("R98,U47,R26,D63,R33,U87,L62,D20,R33,U53,R51\nU98,R91,D20,R16,D67,R40,U7,R15,U6,R7\n", 135, 410),
+];
+
+#[test]
+fn closest_intersections_of_the_examples() {
+    for &(input, distance, _) in EXAMPLES {
+        assert_eq!(part1::<Day3>(input), Answer::Number(distance), "{}", input);
+    }
+}
+
+#[test]
+fn fewest_steps_of_the_examples() {
+    for &(input, _, steps) in EXAMPLES {
+        assert_eq!(part2::<Day3>(input), Answer::Number(steps), "{}", input);
+    }
+}
@@ -1,2 +1,28 @@
 pub mod part1;
 pub mod part2;
+#[cfg(test)]
+mod tests;
+
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution, SolveError};
+
+pub const INPUT: &str = "264360-746325";
+
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = (u64, u64);
+
+    fn parse(input: &str) -> Result<(u64, u64), ParseError> {
+        let (min, max) = parse::pair(parse::single_line(input)?, "-")?;
+        Ok((min.integer()?, max.integer()?))
+    }
+
+    fn part1(&(min, max): &(u64, u64)) -> Result<Answer, SolveError> {
+        Ok(part1::solve(min, max))
+    }
+
+    fn part2(&(min, max): &(u64, u64)) -> Result<Answer, SolveError> {
+        Ok(part2::solve(min, max))
+    }
+}
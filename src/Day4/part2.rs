@@ -1,3 +1,5 @@
+use crate::solution::Answer;
+
 pub fn test_number(num: u64) -> bool {
     let mut last_digit = num % 10;
     let mut number = num / 10;
@@ -33,10 +35,7 @@ pub fn test_number(num: u64) -> bool {
     false
 }
 
-pub fn run_puzzle() {
-    let min = 264360;
-    let max = 746325;
-
+pub fn solve(min: u64, max: u64) -> Answer {
     let mut count = 0;
 
     for num in min..max + 1 {
@@ -45,5 +44,5 @@ pub fn run_puzzle() {
         }
     }
 
-    println!("Result: {}", count);
+    count.into()
 }
@@ -0,0 +1,43 @@
+use super::{part1, part2};
+use crate::testing::check;
+
+#[test]
+fn passwords_of_the_examples() {
+    assert!(part1::test_number(111111));
+    assert!(!part1::test_number(223450));
+    assert!(!part1::test_number(123789));
+}
+
+#[test]
+fn passwords_with_a_pair_of_the_examples() {
+    assert!(part2::test_number(112233));
+    assert!(!part2::test_number(123444));
+    assert!(part2::test_number(111122));
+}
+
+fn digits(num: u64) -> Vec<u64> {
+    num.to_string().bytes().map(|b| (b - b'0') as u64).collect()
+}
+
+#[test]
+fn accepted_passwords_never_decrease() {
+    check(|rng| {
+        for _ in 0..1000 {
+            let num = rng.range(100_000..1_000_000) as u64;
+            if part1::test_number(num) || part2::test_number(num) {
+                assert!(digits(num).windows(2).all(|pair| pair[0] <= pair[1]), "{}", num);
+            }
+        }
+    });
+}
+
+#[test]
+fn passwords_with_a_pair_are_passwords() {
+    check(|rng| {
+        // Sorted digits, so most numbers get past the monotonicity rule
+        let mut digits: Vec<u64> = (0..6).map(|_| rng.range(1..10) as u64).collect();
+        digits.sort_unstable();
+        let num = digits.iter().fold(0, |num, digit| num * 10 + digit);
+        assert!(!part2::test_number(num) || part1::test_number(num), "{}", num);
+    });
+}
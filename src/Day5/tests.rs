@@ -0,0 +1,28 @@
+use super::Day5;
+use crate::solution::Answer;
+use crate::testing::{part1, part2};
+
+// Outputs whether the input is below, equal to or above 8 as 999, 1000 or 1001
+const COMPARE_TO_8: &str = "3,21,1008,21,8,20,1005,20,22,107,8,21,20,1006,20,31,1106,0,36,98,0,0,1002,21,125,20,4,20,1105,1,46,104,999,1105,1,46,1101,1000,1,20,4,20,1105,1,46,98,99";
+
+#[test]
+fn echoes_the_system_id() {
+    assert_eq!(part1::<Day5>("3,0,4,0,99"), Answer::Number(1));
+    assert_eq!(part1::<Day5>("1002,6,3,6,4,6,33"), Answer::Number(99));
+}
+
+#[test]
+fn comparisons_of_the_examples() {
+    // The thermal radiator controller is system 5
+    assert_eq!(part2::<Day5>(COMPARE_TO_8), Answer::Number(999));
+    assert_eq!(part2::<Day5>("3,9,8,9,10,9,4,9,99,-1,8"), Answer::Number(0));
+    assert_eq!(part2::<Day5>("3,9,7,9,10,9,4,9,99,-1,8"), Answer::Number(1));
+    assert_eq!(part2::<Day5>("3,3,1108,-1,8,3,4,3,99"), Answer::Number(0));
+    assert_eq!(part2::<Day5>("3,3,1107,-1,8,3,4,3,99"), Answer::Number(1));
+}
+
+#[test]
+fn jumps_of_the_examples() {
+    assert_eq!(part2::<Day5>("3,12,6,12,15,1,13,14,13,4,13,99,-1,0,1,9"), Answer::Number(1));
+    assert_eq!(part2::<Day5>("3,3,1105,-1,9,1101,0,0,12,4,12,99,1"), Answer::Number(1));
+}
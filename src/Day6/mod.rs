@@ -1,2 +1,65 @@
 pub mod part1;
 pub mod part2;
+#[cfg(test)]
+mod tests;
+
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution, SolveError};
+use std::collections::{HashMap, HashSet};
+
+// (center, object orbiting it)
+pub type Orbit = (String, String);
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    type Input = Vec<Orbit>;
+
+    // Every object orbits a single center and leads to COM, the error points at the first line
+    // breaking that
+    fn parse(input: &str) -> Result<Vec<Orbit>, ParseError> {
+        let mut lines = Vec::new();
+        let mut centers: HashMap<&str, &str> = HashMap::new();
+        for line in parse::lines(input) {
+            let (center, object) = parse::pair(line, ")")?;
+            if object.text == "COM" {
+                return Err(object.error("COM can't orbit anything"));
+            }
+            if let Some(previous) = centers.insert(object.text, center.text) {
+                return Err(object.error(format!("{} already orbits {}", object.text, previous)));
+            }
+            lines.push((line, center.text, object.text));
+        }
+
+        if lines.is_empty() {
+            return Err(parse::whole(input).error("the map has no orbits"));
+        }
+
+        let mut reaching_com: HashSet<&str> = HashSet::new();
+        for (line, center, object) in &lines {
+            let mut path = vec![*object];
+            let mut current = *center;
+            while current != "COM" && !reaching_com.contains(current) {
+                if path.contains(&current) {
+                    return Err(line.error(format!("{} is part of an orbit cycle", current)));
+                }
+                path.push(current);
+                current = match centers.get(current) {
+                    Some(next) => next,
+                    None => return Err(line.error(format!("{} doesn't lead to COM, {} orbits nothing", object, current))),
+                };
+            }
+            reaching_com.extend(path);
+        }
+
+        Ok(lines.iter().map(|(_, center, object)| (center.to_string(), object.to_string())).collect())
+    }
+
+    fn part1(input: &Vec<Orbit>) -> Result<Answer, SolveError> {
+        Ok(part1::solve(input))
+    }
+
+    fn part2(input: &Vec<Orbit>) -> Result<Answer, SolveError> {
+        part2::solve(input)
+    }
+}
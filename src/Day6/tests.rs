@@ -0,0 +1,46 @@
+use super::Day6;
+use crate::solution::{Answer, Solution};
+use crate::testing::{check, part1, part2};
+
+const EXAMPLE: &str = "COM)B\nB)C\nC)D\nD)E\nE)F\nB)G\nG)H\nD)I\nE)J\nJ)K\nK)L\n";
+
+#[test]
+fn orbits_of_the_example() {
+    assert_eq!(part1::<Day6>(EXAMPLE), Answer::Number(42));
+}
+
+#[test]
+fn transfers_of_the_example() {
+    let input = format!("{}K)YOU\nI)SAN\n", EXAMPLE);
+    assert_eq!(part2::<Day6>(&input), Answer::Number(4));
+}
+
+#[test]
+fn orbits_of_a_chain_are_a_triangular_number() {
+    check(|rng| {
+        let n = rng.range(1..50);
+        let mut lines: Vec<String> = (0..n).map(|i| format!("O{})O{}", i, i + 1)).collect();
+        lines[0] = String::from("COM)O1");
+        // The order of the lines doesn't matter
+        for i in (1..lines.len()).rev() {
+            lines.swap(i, rng.range(0..i as i64 + 1) as usize);
+        }
+        assert_eq!(part1::<Day6>(&lines.join("\n")), Answer::Number(n * (n + 1) / 2));
+    });
+}
+
+#[test]
+fn maps_must_be_a_tree_around_com() {
+    let error = |input: &str| Day6::parse(input).err().map(|e| (e.line, e.column, e.message));
+    assert_eq!(error("COM)B\nC)D\n"), Some((2, 1, String::from("D doesn't lead to COM, C orbits nothing"))));
+    assert_eq!(error("COM)B\nB)C\nD)C\n"), Some((3, 3, String::from("C already orbits B"))));
+    assert_eq!(error("COM)B\nC)D\nD)C\n"), Some((2, 1, String::from("D is part of an orbit cycle"))));
+    assert_eq!(error("B)COM\n"), Some((1, 3, String::from("COM can't orbit anything"))));
+    assert_eq!(error(""), Some((1, 1, String::from("the map has no orbits"))));
+}
+
+#[test]
+fn transfers_need_you_and_santa() {
+    let orbits = Day6::parse(&format!("{}K)YOU\n", EXAMPLE)).unwrap();
+    assert_eq!(Day6::part2(&orbits).unwrap_err().to_string(), "SAN isn't in the map");
+}
@@ -1,2 +1,35 @@
 pub mod part1;
 pub mod part2;
+#[cfg(test)]
+mod tests;
+
+use crate::export::Raster;
+use crate::parse::{self, ParseError};
+use crate::solution::{Answer, Solution, SolveError};
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = String;
+
+    fn parse(input: &str) -> Result<String, ParseError> {
+        let line = parse::single_line(input)?.check_chars("012", "pixel")?;
+        // Layers are 25 by 6 pixels
+        if line.text.len() % 150 != 0 {
+            return Err(line.error(format!("{} pixels don't make whole layers of 25 by 6", line.text.len())));
+        }
+        Ok(line.text.to_string())
+    }
+
+    fn part1(input: &String) -> Result<Answer, SolveError> {
+        Ok(part1::solve(input))
+    }
+
+    fn part2(input: &String) -> Result<Answer, SolveError> {
+        part2::solve(input)
+    }
+
+    fn picture(input: &String) -> Result<Option<Raster>, SolveError> {
+        Ok(Some(Raster::from_image(&part2::decode(input))))
+    }
+}
@@ -1,11 +1,6 @@
-use std::fs::File;
-use std::io::Read;
-
-pub fn run_puzzle() {
-    let mut file = File::open("input_day8.txt").expect("Failed to open input_day8.txt");
-    let mut img_str = String::new();
-    file.read_to_string(&mut img_str).unwrap();
+use crate::solution::Answer;
 
+pub fn solve(img_str: &str) -> Answer {
     let wide = 25;
     let tall = 6;
 
@@ -30,16 +25,16 @@ pub fn run_puzzle() {
         num_chars.push(chars);
     }
 
-    let (mut num_zero, mut layer) = (std::u64::MAX, 0);
+    let (mut num_zero, mut layer) = (u64::MAX, 0);
 
-    for i in 0..num_chars.len() {
-        if num_chars[i][0] < num_zero {
-            num_zero = num_chars[i][0];
+    for (i, chars) in num_chars.iter().enumerate() {
+        if chars[0] < num_zero {
+            num_zero = chars[0];
             layer = i;
         }
     }
 
     let result = num_chars[layer][1] * num_chars[layer][2];
 
-    println!("Result is: {}", result);
+    result.into()
 }
@@ -1,11 +1,8 @@
-use std::fs::File;
-use std::io::Read;
-
-pub fn run_puzzle() {
-    let mut file = File::open("input_day8.txt").expect("Failed to open input_day8.txt");
-    let mut img_str = String::new();
-    file.read_to_string(&mut img_str).unwrap();
+use crate::ocr;
+use crate::solution::{Answer, Image, SolveError};
 
+// Picture left once the transparent pixels of the layers are stacked
+pub fn decode(img_str: &str) -> Image {
     let wide = 25;
     let tall = 6;
 
@@ -22,24 +19,29 @@ pub fn run_puzzle() {
 
     let mut img_result = vec!['2'; wide * tall];
 
-    for i in 0..wide * tall {
+    for (i, pixel) in img_result.iter_mut().enumerate() {
         for layer in &layers {
-            img_result[i] = layer.chars().nth(i).unwrap();
-            if img_result[i] != '2' {
+            *pixel = layer.chars().nth(i).unwrap();
+            if *pixel != '2' {
                 break;
             }
         }
     }
 
+    let mut image = Image::new(wide, tall);
     for y in 0..tall {
-        let mut rstr = String::new();
         for x in 0..wide {
             match img_result[(y * wide) + x] {
-                '0' | '2' => rstr.push(' '),
-                '1' => rstr.push('X'),
+                '0' | '2' => image.set(x, y, false),
+                '1' => image.set(x, y, true),
                 _ => panic!("Unexpected char"),
             }
         }
-        println!("{}", rstr);
     }
+
+    image
+}
+
+pub fn solve(img_str: &str) -> Result<Answer, SolveError> {
+    ocr::answer(decode(img_str))
 }
@@ -0,0 +1,27 @@
+use super::{part1, part2};
+use crate::solution::Answer;
+
+// Layers of 25 by 6 pixels
+fn layer(zeros: usize, ones: usize, twos: usize) -> String {
+    assert_eq!(zeros + ones + twos, 150);
+    "0".repeat(zeros) + &"1".repeat(ones) + &"2".repeat(twos)
+}
+
+#[test]
+fn checksum_of_the_layer_with_fewest_zeros() {
+    let image = layer(10, 20, 120) + &layer(5, 100, 45) + &layer(50, 50, 50);
+    assert_eq!(part1::solve(&image), Answer::Number(4500));
+}
+
+#[test]
+fn transparent_pixels_show_the_layers_below() {
+    let mut top = "2".repeat(150);
+    top.replace_range(0..1, "0");
+    let mut middle = "2".repeat(150);
+    middle.replace_range(0..2, "11");
+    let bottom = "0".repeat(149) + "1";
+
+    let image = part2::decode(&(top + &middle + &bottom));
+    let lit: Vec<(usize, usize)> = (0..6).flat_map(|y| (0..25).map(move |x| (x, y))).filter(|&(x, y)| image.get(x, y)).collect();
+    assert_eq!(lit, vec![(1, 0), (24, 5)]);
+}
@@ -0,0 +1,17 @@
+use super::Day9;
+use crate::solution::Answer;
+use crate::testing::part1;
+
+#[test]
+fn large_numbers() {
+    assert_eq!(part1::<Day9>("104,1125899906842624,99"), Answer::Number(1125899906842624));
+    assert_eq!(part1::<Day9>("1102,34915192,34915192,7,4,7,99,0"), Answer::Number(1219070632396864));
+}
+
+#[test]
+fn relative_mode() {
+    // Stores the input past the end of the program and outputs it back
+    assert_eq!(part1::<Day9>("109,10,203,0,204,0,99"), Answer::Number(1));
+    // Quine, its last output is the final 99
+    assert_eq!(part1::<Day9>("109,1,204,-1,1001,100,1,100,1008,100,16,101,1006,101,0,99"), Answer::Number(99));
+}
@@ -0,0 +1,297 @@
+// Recording of the simulations as a sequence of frames.
+//
+// The days call `capture` after every step with a closure drawing the current state, only every
+// `every`-th step is drawn and kept, and the last state always is. Frames of sparse grids keep
+// their position so they are all drawn on the smallest canvas holding every one of them.
+//
+// Frames are saved as an animated GIF looping forever, with a delay between frames in milliseconds
+// (GIF counts in hundredths of a second), or as a directory of `frame_NNNNN.ppm` files.
+//
+// A live recorder draws the frames in the terminal as they come instead, skipping the ones coming
+// faster than its frame rate.
+
+use crate::export::{Raster, Rgb, BLACK};
+use crate::grid::{Grid, Point, SparseGrid};
+use crate::terminal::Terminal;
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+pub struct Frame {
+    origin: Point,
+    raster: Raster,
+}
+
+impl Frame {
+    pub fn grid<T, F: Fn(&T) -> Rgb>(grid: &Grid<T>, palette: F) -> Frame {
+        Frame {
+            origin: (0, 0),
+            raster: Raster::from_grid(grid, palette),
+        }
+    }
+
+    // Area of the cells set, empty when there are none
+    pub fn sparse<T: Clone, F: Fn(&T) -> Rgb>(grid: &SparseGrid<T>, palette: F) -> Frame {
+        match grid.bounding_box(|_| true) {
+            Some(bounds) => Frame {
+                origin: bounds.0,
+                raster: Raster::from_grid(&grid.to_grid(bounds), palette),
+            },
+            None => Frame {
+                origin: (0, 0),
+                raster: Raster {
+                    width: 0,
+                    height: 0,
+                    pixels: Vec::new(),
+                },
+            },
+        }
+    }
+
+    // Marks a point, like the position of a robot, left out when it is outside of the frame
+    pub fn mark(mut self, (x, y): Point, colour: Rgb) -> Frame {
+        let (x, y) = (x - self.origin.0, y - self.origin.1);
+        if x >= 0 && y >= 0 && (x as usize) < self.raster.width && (y as usize) < self.raster.height {
+            self.raster.pixels[y as usize * self.raster.width + x as usize] = colour;
+        }
+        self
+    }
+
+    pub fn origin(&self) -> Point {
+        self.origin
+    }
+
+    // First point right and below the frame
+    pub fn end(&self) -> Point {
+        (self.origin.0 + self.raster.width as i64, self.origin.1 + self.raster.height as i64)
+    }
+
+    // Raster of the given size with its top left corner at `origin` and the frame drawn in it
+    pub fn placed(&self, origin: Point, (width, height): (usize, usize)) -> Raster {
+        let mut raster = Raster {
+            width,
+            height,
+            pixels: vec![BLACK; width * height],
+        };
+        let (dx, dy) = ((self.origin.0 - origin.0) as usize, (self.origin.1 - origin.1) as usize);
+        for y in 0..self.raster.height {
+            for x in 0..self.raster.width {
+                raster.pixels[(y + dy) * width + x + dx] = self.raster.get(x, y);
+            }
+        }
+        raster
+    }
+}
+
+pub struct Recorder {
+    enabled: bool,
+    every: usize,
+    steps: usize,
+    last_kept: bool,
+    frames: Vec<Frame>,
+    live: Option<Terminal>,
+}
+
+impl Recorder {
+    pub fn new(every: usize) -> Recorder {
+        Recorder {
+            enabled: true,
+            every,
+            steps: 0,
+            last_kept: true,
+            frames: Vec::new(),
+            live: None,
+        }
+    }
+
+    // Draws the frames in the terminal instead of keeping them, when it is due for one
+    pub fn live(terminal: Terminal) -> Recorder {
+        Recorder {
+            live: Some(terminal),
+            ..Recorder::new(1)
+        }
+    }
+
+    // Recorder of the normal runs, the frames are never drawn
+    pub fn disabled() -> Recorder {
+        Recorder { enabled: false, ..Recorder::new(1) }
+    }
+
+    pub fn capture<F: FnOnce() -> Frame>(&mut self, frame: F) {
+        if !self.enabled {
+            return;
+        }
+        self.last_kept = match &self.live {
+            Some(terminal) => terminal.is_due(),
+            None => self.steps.is_multiple_of(self.every),
+        };
+        if self.last_kept {
+            self.keep(frame());
+        }
+        self.steps += 1;
+    }
+
+    // Final state, kept unless the last step already was
+    pub fn finish<F: FnOnce() -> Frame>(&mut self, frame: F) {
+        if self.enabled && !self.last_kept {
+            self.keep(frame());
+        }
+        self.last_kept = true;
+    }
+
+    fn keep(&mut self, frame: Frame) {
+        match &mut self.live {
+            Some(terminal) => terminal.draw(&frame),
+            None => self.frames.push(frame),
+        }
+    }
+
+    // Frames drawn on a common canvas
+    pub fn rasters(&self) -> Vec<Raster> {
+        let min = self.frames.iter().map(|f| f.origin).fold((i64::MAX, i64::MAX), |min, o| (min.0.min(o.0), min.1.min(o.1)));
+        let max = self.frames.iter().map(|f| f.end()).fold((i64::MIN, i64::MIN), |max, e| (max.0.max(e.0), max.1.max(e.1)));
+        let size = ((max.0 - min.0).max(0) as usize, (max.1 - min.1).max(0) as usize);
+        self.frames.iter().map(|frame| frame.placed(min, size)).collect()
+    }
+}
+
+// Variable length LZW codes packed from the least significant bit
+struct BitWriter {
+    bytes: Vec<u8>,
+    buffer: u32,
+    bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, size: u32) {
+        self.buffer |= (code as u32) << self.bits;
+        self.bits += size;
+        while self.bits >= 8 {
+            self.bytes.push(self.buffer as u8);
+            self.buffer >>= 8;
+            self.bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.bits > 0 {
+            self.bytes.push(self.buffer as u8);
+        }
+        self.bytes
+    }
+}
+
+fn lzw(indices: &[u8], min_code_size: u32) -> Vec<u8> {
+    let clear = 1u16 << min_code_size;
+    let end = clear + 1;
+    let mut writer = BitWriter {
+        bytes: Vec::new(),
+        buffer: 0,
+        bits: 0,
+    };
+    let mut codes: HashMap<(u16, u8), u16> = HashMap::new();
+    let (mut next, mut size) = (end + 1, min_code_size + 1);
+
+    writer.write(clear, size);
+    let mut prefix = match indices.first() {
+        Some(&index) => index as u16,
+        None => {
+            writer.write(end, size);
+            return writer.finish();
+        }
+    };
+
+    for &index in &indices[1..] {
+        if let Some(&code) = codes.get(&(prefix, index)) {
+            prefix = code;
+            continue;
+        }
+
+        writer.write(prefix, size);
+        if next == 4096 {
+            writer.write(clear, size);
+            codes.clear();
+            next = end + 1;
+            size = min_code_size + 1;
+        } else {
+            if next >= 1 << size {
+                size += 1;
+            }
+            codes.insert((prefix, index), next);
+            next += 1;
+        }
+        prefix = index as u16;
+    }
+
+    writer.write(prefix, size);
+    writer.write(end, size);
+    writer.finish()
+}
+
+pub fn to_gif(frames: &[Raster], delay_ms: u32) -> Result<Vec<u8>, String> {
+    let (width, height) = frames.first().map_or((0, 0), |f| (f.width, f.height));
+
+    let mut palette: Vec<Rgb> = Vec::new();
+    let mut colours: HashMap<Rgb, u8> = HashMap::new();
+    for &pixel in frames.iter().flat_map(|f| f.pixels.iter()) {
+        if let Entry::Vacant(entry) = colours.entry(pixel) {
+            if palette.len() == 256 {
+                return Err(String::from("GIF frames can't have more than 256 colours"));
+            }
+            entry.insert(palette.len() as u8);
+            palette.push(pixel);
+        }
+    }
+
+    // The colour table has 2^bits entries, at least 4 for the smallest LZW code size
+    let bits = (2..=8).find(|bits| palette.len() <= 1 << bits).unwrap();
+    palette.resize(1 << bits, BLACK);
+
+    let mut gif = b"GIF89a".to_vec();
+    gif.extend_from_slice(&(width as u16).to_le_bytes());
+    gif.extend_from_slice(&(height as u16).to_le_bytes());
+    gif.extend_from_slice(&[0x80 | (bits as u8 - 1), 0, 0]);
+    gif.extend(palette.iter().flatten());
+
+    // Netscape extension, looping forever
+    gif.extend_from_slice(b"\x21\xff\x0bNETSCAPE2.0\x03\x01\x00\x00\x00");
+
+    let delay = ((delay_ms + 5) / 10) as u16;
+    for frame in frames {
+        gif.extend_from_slice(&[0x21, 0xf9, 4, 0]);
+        gif.extend_from_slice(&delay.to_le_bytes());
+        gif.extend_from_slice(&[0, 0]);
+
+        gif.push(0x2c);
+        gif.extend_from_slice(&[0, 0, 0, 0]);
+        gif.extend_from_slice(&(width as u16).to_le_bytes());
+        gif.extend_from_slice(&(height as u16).to_le_bytes());
+        gif.push(0);
+
+        let indices: Vec<u8> = frame.pixels.iter().map(|pixel| colours[pixel]).collect();
+        gif.push(bits as u8);
+        for block in lzw(&indices, bits).chunks(255) {
+            gif.push(block.len() as u8);
+            gif.extend_from_slice(block);
+        }
+        gif.push(0);
+    }
+
+    gif.push(0x3b);
+    Ok(gif)
+}
+
+// Writes the frames as an animated GIF when the output ends in `.gif`, as a directory of PPM files otherwise
+pub fn save(frames: &[Raster], output: &str, delay_ms: u32) -> Result<(), String> {
+    if output.ends_with(".gif") {
+        return fs::write(output, to_gif(frames, delay_ms)?).map_err(|e| format!("Failed to write {}: {}", output, e));
+    }
+
+    fs::create_dir_all(output).map_err(|e| format!("Failed to create {}: {}", output, e))?;
+    for (index, frame) in frames.iter().enumerate() {
+        let path = Path::new(output).join(format!("frame_{:05}.ppm", index));
+        fs::write(&path, frame.to_ppm()).map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
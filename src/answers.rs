@@ -0,0 +1,155 @@
+// Expected answers of every day, used by the runner to catch regressions.
+//
+// The file is a small subset of TOML, one table per day and one string per part:
+//   [day1]
+//   part1 = "3495189"
+//   part2 = "5239910"
+// Images are stored with their rows separated by `\n`. `#` starts a comment line.
+
+use crate::solution::Answer;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail(String),
+    New,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "PASS"),
+            Verdict::Fail(expected) if expected.contains('\n') => write!(f, "FAIL, expected:\n{}", expected),
+            Verdict::Fail(expected) => write!(f, "FAIL, expected {}", expected),
+            Verdict::New => write!(f, "NEW"),
+        }
+    }
+}
+
+// Text an answer is stored and compared as
+fn answer_text(answer: &Answer) -> String {
+    match answer {
+        Answer::Image(image) => image.to_string(),
+        answer => answer.to_string(),
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::new();
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '"' => escaped.push_str("\\\""),
+            '\n' => escaped.push_str("\\n"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(text: &str) -> Option<String> {
+    let mut unescaped = String::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                '\\' => unescaped.push('\\'),
+                '"' => unescaped.push('"'),
+                'n' => unescaped.push('\n'),
+                _ => return None,
+            },
+            '"' => return None,
+            c => unescaped.push(c),
+        }
+    }
+    Some(unescaped)
+}
+
+#[derive(Default)]
+pub struct AnswerFile {
+    answers: BTreeMap<(u32, usize), String>,
+}
+
+impl AnswerFile {
+    pub fn parse(text: &str) -> Result<AnswerFile, String> {
+        let mut file = AnswerFile::default();
+        let mut day = None;
+
+        for (index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            let error = || format!("{}:{}: invalid line `{}`", ANSWERS_FILE, index + 1, line);
+
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            if let Some(table) = line.strip_prefix('[') {
+                let number = table.strip_suffix(']').and_then(|t| t.strip_prefix("day")).and_then(|n| n.parse().ok()).ok_or_else(error)?;
+                day = Some(number);
+                continue;
+            }
+
+            let mut key_value = line.splitn(2, '=');
+            let (key, value) = (key_value.next().unwrap().trim(), key_value.next().ok_or_else(error)?.trim());
+            let part = match key {
+                "part1" => 1,
+                "part2" => 2,
+                _ => return Err(error()),
+            };
+            let value = value.strip_prefix('"').and_then(|v| v.strip_suffix('"')).and_then(unescape).ok_or_else(error)?;
+
+            file.answers.insert((day.ok_or_else(error)?, part), value);
+        }
+
+        Ok(file)
+    }
+
+    // A missing file has no answers yet
+    pub fn load(path: &str) -> Result<AnswerFile, String> {
+        match fs::read_to_string(path) {
+            Ok(text) => AnswerFile::parse(&text),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(AnswerFile::default()),
+            Err(e) => Err(format!("Failed to open {}: {}", path, e)),
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut text = String::from("# Expected answers, updated with `run --record`\n\n");
+        let mut current_day = None;
+
+        for (&(day, part), answer) in &self.answers {
+            if current_day != Some(day) {
+                if current_day.is_some() {
+                    text.push('\n');
+                }
+                text += &format!("[day{}]\n", day);
+                current_day = Some(day);
+            }
+            text += &format!("part{} = \"{}\"\n", part, escape(answer));
+        }
+
+        text
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        fs::write(path, self.to_text()).map_err(|e| format!("Failed to write {}: {}", path, e))
+    }
+
+    pub fn check(&self, day: u32, part: usize, answer: &Answer) -> Verdict {
+        match self.answers.get(&(day, part)) {
+            Some(expected) if *expected == answer_text(answer) => Verdict::Pass,
+            Some(expected) => Verdict::Fail(expected.clone()),
+            None => Verdict::New,
+        }
+    }
+
+    pub fn record(&mut self, day: u32, part: usize, answer: &Answer) {
+        self.answers.insert((day, part), answer_text(answer));
+    }
+}
@@ -0,0 +1,152 @@
+// Timing of the parse step and of each part of the puzzle solutions.
+//
+// Every day is solved a number of times, the table shows the median time of each step, the CSV and
+// JSON reports also have the min, mean and max.
+
+use crate::intcode::session::Sessions;
+use crate::runner::Day;
+use crate::solution::SolveError;
+use std::time::Duration;
+
+pub struct Stats {
+    pub min: Duration,
+    pub median: Duration,
+    pub mean: Duration,
+    pub max: Duration,
+}
+
+impl Stats {
+    fn new(mut times: Vec<Duration>) -> Stats {
+        times.sort_unstable();
+        Stats {
+            min: times[0],
+            median: times[times.len() / 2],
+            mean: times.iter().sum::<Duration>() / times.len() as u32,
+            max: times[times.len() - 1],
+        }
+    }
+}
+
+// Stats of one step of a day, the step being "parse", "part1" or "part2"
+pub struct Measure {
+    pub day: u32,
+    pub step: &'static str,
+    pub stats: Stats,
+}
+
+fn ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+pub fn to_csv(measures: &[Measure], runs: usize) -> String {
+    let mut csv = String::from("day,step,runs,min_ms,median_ms,mean_ms,max_ms\n");
+    for m in measures {
+        csv += &format!(
+            "{},{},{},{:.3},{:.3},{:.3},{:.3}\n",
+            m.day,
+            m.step,
+            runs,
+            ms(m.stats.min),
+            ms(m.stats.median),
+            ms(m.stats.mean),
+            ms(m.stats.max)
+        );
+    }
+    csv
+}
+
+pub fn to_json(measures: &[Measure], runs: usize) -> String {
+    let entries: Vec<String> = measures
+        .iter()
+        .map(|m| {
+            format!(
+                "  {{\"day\": {}, \"step\": \"{}\", \"runs\": {}, \"min_ms\": {:.3}, \"median_ms\": {:.3}, \"mean_ms\": {:.3}, \"max_ms\": {:.3}}}",
+                m.day,
+                m.step,
+                runs,
+                ms(m.stats.min),
+                ms(m.stats.median),
+                ms(m.stats.mean),
+                ms(m.stats.max)
+            )
+        })
+        .collect();
+    format!("[\n{}\n]\n", entries.join(",\n"))
+}
+
+// Table of the median time of every step, days in rows
+pub fn table(measures: &[Measure]) -> String {
+    let mut table = format!("{:>4} {:>12} {:>12} {:>12} {:>12}\n", "Day", "parse", "part 1", "part 2", "total");
+
+    let mut days: Vec<u32> = measures.iter().map(|m| m.day).collect();
+    days.dedup();
+    for day in days {
+        let median = |step: &str| measures.iter().find(|m| m.day == day && m.step == step).map(|m| m.stats.median);
+        let cell = |time: Option<Duration>| time.map_or_else(|| String::from("-"), |t| format!("{:.3} ms", ms(t)));
+        let total = ["parse", "part1", "part2"].iter().filter_map(|step| median(step)).sum();
+        table += &format!(
+            "{:>4} {:>12} {:>12} {:>12} {:>12}\n",
+            day,
+            cell(median("parse")),
+            cell(median("part1")),
+            cell(median("part2")),
+            cell(Some(total))
+        );
+    }
+
+    if let Some(slowest) = measures.iter().max_by_key(|m| m.stats.median) {
+        table += &format!("Slowest: day {} {} ({:.3} ms)\n", slowest.day, slowest.step, ms(slowest.stats.median));
+    }
+    table
+}
+
+// Solves the parts of the day `runs` times, returns the stats of the parse step then of every part
+pub fn measure(day: &Day, input: &str, parts: &[usize], runs: usize) -> Result<Vec<Measure>, SolveError> {
+    if runs == 0 {
+        return Err(SolveError::Failed(String::from("Timing needs at least one run")));
+    }
+
+    let mut parse_times = Vec::new();
+    let mut part_times = vec![Vec::new(); parts.len()];
+    for _ in 0..runs {
+        let result = (day.solve)(input, parts, &mut Sessions::disabled())?;
+        parse_times.push(result.parse_time);
+        for (times, part) in part_times.iter_mut().zip(result.parts) {
+            part.answer?;
+            times.push(part.time);
+        }
+    }
+
+    let mut measures = vec![Measure {
+        day: day.number,
+        step: "parse",
+        stats: Stats::new(parse_times),
+    }];
+    for (&part, times) in parts.iter().zip(part_times) {
+        measures.push(Measure {
+            day: day.number,
+            step: if part == 1 { "part1" } else { "part2" },
+            stats: Stats::new(times),
+        });
+    }
+    Ok(measures)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runner::find_day;
+
+    #[test]
+    fn measures_every_step() {
+        let measures = measure(find_day(1).unwrap(), "12\n14\n", &[1, 2], 3).unwrap();
+        let steps: Vec<&str> = measures.iter().map(|m| m.step).collect();
+        assert_eq!(steps, ["parse", "part1", "part2"]);
+        assert!(measures.iter().all(|m| m.stats.min <= m.stats.median && m.stats.median <= m.stats.max));
+    }
+
+    #[test]
+    fn zero_runs_is_an_error() {
+        assert!(measure(find_day(1).unwrap(), "12\n", &[1], 0).is_err());
+    }
+}
@@ -0,0 +1,56 @@
+// The `animate` command recording the simulation of a day to a file.
+//
+//   animate <day> <output> [--input PATH] [--every N] [--scale N] [--delay MS]
+// Only every `--every`-th step of the simulation is kept, and the last one always is. An output
+// ending in `.gif` is written as an animated GIF, `--delay` being the time between frames (50 ms by
+// default). Any other output is a directory receiving one `frame_NNNNN.ppm` file per frame.
+
+use crate::cli::day_error;
+use advent_of_code::animation::{self, Recorder};
+use advent_of_code::export::Raster;
+use advent_of_code::runner::{self, find_day};
+
+const DEFAULT_DELAY_MS: u32 = 50;
+
+// Entry point for `animate <day> <output> [--input PATH] [--every N] [--scale N] [--delay MS]`
+pub fn animate_command(args: &[String]) -> Result<(), String> {
+    let (mut input, mut every, mut scale, mut delay) = (None, 1, 1, DEFAULT_DELAY_MS);
+    let mut positional = Vec::new();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => input = Some(args.next().ok_or_else(|| String::from("--input needs a path"))?.as_str()),
+            option @ "--every" | option @ "--scale" | option @ "--delay" => {
+                let value = match args.next().map(|n| n.parse()) {
+                    Some(Ok(n)) if n > 0 => n,
+                    _ => return Err(format!("{} needs a positive number", option)),
+                };
+                match option {
+                    "--every" => every = value,
+                    "--scale" => scale = value,
+                    _ => delay = value as u32,
+                }
+            }
+            arg if arg.starts_with("--") => return Err(format!("unknown option `{}`", arg)),
+            arg => positional.push(arg),
+        }
+    }
+
+    let (day, output) = match positional[..] {
+        [day, output] => (day, output),
+        _ => return Err(String::from("animate needs a day and an output")),
+    };
+    let day = day.parse().ok().and_then(find_day).ok_or_else(|| format!("unknown day `{}`", day))?;
+
+    let text = runner::read_input(day, input)?;
+    let mut recorder = Recorder::new(every);
+    if !(day.animate)(&text, &mut recorder).map_err(|e| day_error(day, input, e))? {
+        return Err(format!("Day {} has no simulation to animate", day.number));
+    }
+
+    let frames: Vec<Raster> = recorder.rasters().iter().map(|raster| raster.scaled(scale)).collect();
+    animation::save(&frames, output, delay)?;
+    println!("{} frames written to {}", frames.len(), output);
+    Ok(())
+}
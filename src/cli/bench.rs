@@ -0,0 +1,55 @@
+// The `bench` command timing the parse step and each part of the selected days.
+//
+//   bench [days...] [--part N] [--input PATH] [--runs N] [--csv PATH] [--json PATH]
+// Days are selected like for `run`. Every day is solved `--runs` times (5 by default) and the table
+// shows the median time of each step, the CSV and JSON reports also have the min, mean and max.
+
+use crate::cli::{day_error, run};
+use advent_of_code::bench;
+use advent_of_code::runner::{self, find_day};
+use std::fs;
+
+const DEFAULT_RUNS: usize = 5;
+
+// Entry point for `bench [days...] [--part N] [--input PATH] [--runs N] [--csv PATH] [--json PATH]`
+pub fn bench_command(args: &[String]) -> Result<(), String> {
+    let (mut runs, mut csv_path, mut json_path) = (DEFAULT_RUNS, None, None);
+    let mut run_args = Vec::new();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--runs" => {
+                runs = match args.next().map(|n| n.parse()) {
+                    Some(Ok(n)) if n > 0 => n,
+                    _ => return Err(String::from("--runs needs a positive number")),
+                }
+            }
+            "--csv" => csv_path = Some(args.next().ok_or_else(|| String::from("--csv needs a path"))?),
+            "--json" => json_path = Some(args.next().ok_or_else(|| String::from("--json needs a path"))?),
+            "--record" | "--format" | "--visualize" | "--fps" => return Err(format!("{} can only be used with run", arg)),
+            _ => run_args.push(arg.clone()),
+        }
+    }
+
+    let options = run::parse_args(&run_args)?;
+    let parts = options.parts();
+    let mut measures = Vec::new();
+
+    for number in &options.days {
+        let day = find_day(*number).unwrap();
+        let input = runner::read_input(day, options.input.as_deref())?;
+        measures.extend(bench::measure(day, &input, &parts, runs).map_err(|e| day_error(day, options.input.as_deref(), e))?);
+    }
+
+    print!("{}", bench::table(&measures));
+
+    if let Some(path) = csv_path {
+        fs::write(path, bench::to_csv(&measures, runs)).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    }
+    if let Some(path) = json_path {
+        fs::write(path, bench::to_json(&measures, runs)).map_err(|e| format!("Failed to write {}: {}", path, e))?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+// The `export` command saving the picture of a day as an image file.
+//
+//   export <day> <output> [--input PATH] [--scale N]
+// The format comes from the extension of the output: `.ppm`, `.png` or `.svg`, each cell of the
+// picture is drawn as a square of `--scale` pixels (1 by default).
+
+use crate::cli::day_error;
+use advent_of_code::runner::{self, find_day};
+
+// Entry point for `export <day> <output> [--input PATH] [--scale N]`
+pub fn export_command(args: &[String]) -> Result<(), String> {
+    let (mut input, mut scale) = (None, 1);
+    let mut positional = Vec::new();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--input" => input = Some(args.next().ok_or_else(|| String::from("--input needs a path"))?.as_str()),
+            "--scale" => {
+                scale = match args.next().map(|n| n.parse()) {
+                    Some(Ok(n)) if n > 0 => n,
+                    _ => return Err(String::from("--scale needs a positive number")),
+                }
+            }
+            arg if arg.starts_with("--") => return Err(format!("unknown option `{}`", arg)),
+            arg => positional.push(arg),
+        }
+    }
+
+    let (day, output) = match positional[..] {
+        [day, output] => (day, output),
+        _ => return Err(String::from("export needs a day and an output file")),
+    };
+    let day = day.parse().ok().and_then(find_day).ok_or_else(|| format!("unknown day `{}`", day))?;
+
+    let text = runner::read_input(day, input)?;
+    match (day.picture)(&text).map_err(|e| day_error(day, input, e))? {
+        Some(raster) => raster.save(output, scale),
+        None => Err(format!("Day {} has no picture to export", day.number)),
+    }
+}
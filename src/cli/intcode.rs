@@ -0,0 +1,132 @@
+// The `intcode-*` commands working on Intcode program files.
+//
+// Programs are loaded in any format the loader accepts, inputs given on the command line are comma
+// or space separated lists of values.
+
+use advent_of_code::intcode::compiler::codegen;
+use advent_of_code::intcode::dump::MemoryDump;
+use advent_of_code::intcode::session::{self, Session};
+use advent_of_code::intcode::vm::Program;
+use advent_of_code::intcode::{golden, loader, optimise, threaded};
+use std::fs;
+use std::path::Path;
+
+fn load(program_path: &str) -> Result<Vec<i64>, String> {
+    loader::load_file(program_path).map_err(|e| e.to_string())
+}
+
+fn parse_inputs(inputs: &[String]) -> Result<Vec<Vec<i64>>, String> {
+    inputs.iter().map(|s| loader::parse_text(s).map_err(|e| e.to_string())).collect()
+}
+
+// Entry point for `intcode-test <program> <cases_dir>`, returns whether every case passed
+pub fn test_command(program_path: &str, cases_dir: &str) -> Result<bool, String> {
+    let image = load(program_path)?;
+    let cases = golden::load_cases(Path::new(cases_dir))?;
+    if cases.is_empty() {
+        return Err(format!("{}: no .case files found", cases_dir));
+    }
+
+    let result = golden::run_suite(&image, &cases);
+    println!("{}", result);
+    Ok(result.num_failed() == 0)
+}
+
+// Entry point for `intcode-dump <program> [inputs...]`, runs until the program stops and dumps its memory
+pub fn dump_command(program_path: &str, inputs: &[String]) -> Result<(), String> {
+    let image = load(program_path)?;
+    let mut input = Vec::new();
+    for value in inputs {
+        input.push(value.parse().map_err(|_| format!("invalid input value `{}`", value))?);
+    }
+
+    let mut program = Program::new(&image);
+    let initial = MemoryDump::capture(&program);
+    let state = program.run_with_input(&input).map_err(|e| e.to_string())?;
+    let dump = MemoryDump::capture(&program);
+
+    println!("Program {} after producing {} outputs", state, program.output().len());
+    println!();
+    print!("{}", dump.render_regions());
+    println!();
+    print!("{}", dump.render(0..dump.memory.len()));
+    println!();
+    print!("{}", initial.render_diff(&dump));
+
+    Ok(())
+}
+
+// Entry point for `intcode-optimise <program> <output> [sample inputs...]`, each sample is a comma separated list
+pub fn optimise_command(program_path: &str, output_path: &str, samples: &[String]) -> Result<(), String> {
+    let image = load(program_path)?;
+    let samples = parse_inputs(samples)?;
+
+    let (optimised, report) = optimise::optimise(&image);
+    println!("{}", report);
+
+    for (i, comparison) in optimise::verify(&image, &optimised, &samples)?.iter().enumerate() {
+        let percent = 100.0 * comparison.saved() as f64 / comparison.original_steps.max(1) as f64;
+        println!(
+            "sample {}: {} -> {} steps, {} saved ({:.1}%)",
+            i,
+            comparison.original_steps,
+            comparison.optimised_steps,
+            comparison.saved(),
+            percent
+        );
+    }
+
+    loader::save_file(output_path, &optimised, loader::Format::Text).map_err(|e| format!("{}: {}", output_path, e))
+}
+
+// Entry point for `intcode-compile <source> <output>`
+pub fn compile_command(source_path: &str, output_path: &str) -> Result<(), String> {
+    let source = fs::read_to_string(source_path).map_err(|e| format!("{}: {}", source_path, e))?;
+    let image = codegen::compile(&source).map_err(|e| match e.pos {
+        Some(_) => format!("{}:{}", source_path, e),
+        None => format!("{}: {}", source_path, e),
+    })?;
+    loader::save_file(output_path, &image, loader::Format::Text).map_err(|e| format!("{}: {}", output_path, e))?;
+    println!("{} values written to {}", image.len(), output_path);
+    Ok(())
+}
+
+// Entry point for `intcode-record <program> <session> [inputs...]`
+pub fn record_command(program_path: &str, session_path: &str, inputs: &[String]) -> Result<(), String> {
+    let image = load(program_path)?;
+    let (session, state) = session::record(&image, &parse_inputs(inputs)?).map_err(|e| e.to_string())?;
+    session.save_file(session_path)?;
+    println!("{} events recorded, program {}", session.entries.len(), state);
+    Ok(())
+}
+
+// Entry point for `intcode-replay <program> <session>`, returns whether the replay matched
+pub fn replay_command(program_path: &str, session_path: &str) -> Result<bool, String> {
+    let image = load(program_path)?;
+    let session = Session::load_file(session_path)?;
+
+    match session::replay(&image, &session) {
+        Ok(matched) => {
+            println!("{} events matched", matched);
+            Ok(true)
+        }
+        Err(divergence) => {
+            println!("diverged at {}", divergence);
+            Ok(false)
+        }
+    }
+}
+
+// Entry point for `intcode-bench <program> [inputs...]`, runs the program on both engines
+pub fn bench_command(program_path: &str, input: &[String]) -> Result<(), String> {
+    let image = load(program_path)?;
+    let input = loader::parse_text(&input.join(",")).map_err(|e| e.to_string())?;
+    let times = threaded::compare_engines(&image, &input)?;
+
+    println!("{} steps, {} instructions translated ahead of time", times.steps, times.translated);
+    for (name, time) in &[("interpreter", times.interpreter), ("threaded", times.threaded)] {
+        let seconds = time.as_secs_f64();
+        println!("{:<12} {:>10.3} ms {:>8.1} Msteps/s", name, seconds * 1000.0, times.steps as f64 / seconds / 1e6);
+    }
+    Ok(())
+}
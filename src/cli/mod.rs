@@ -0,0 +1,16 @@
+// Command line front end over the library: argument parsing, printing and exit statuses.
+
+pub mod animate;
+pub mod bench;
+pub mod export;
+pub mod intcode;
+pub mod run;
+
+use advent_of_code::runner::{self, Day};
+use advent_of_code::solution::SolveError;
+
+// Message of a day failing to solve, naming the input it was given
+fn day_error(day: &Day, input: Option<&str>, e: SolveError) -> String {
+    let path = runner::input_path(day, input).unwrap_or("<embedded>");
+    format!("Day {}: {}", day.number, e.with_path(path))
+}
@@ -0,0 +1,277 @@
+// The `run` command solving the selected days and checking their answers.
+//
+//   run [days...] [--part N] [--input PATH] [--record] [--format text|json] [--visualize] [--fps N]
+// Days are given as numbers or ranges like `10-13`, all of them run when none is given. The input
+// file can only be overridden when running a single day, `--input -` reads it from stdin. Days 4, 12
+// and 16 default to the input embedded in their module.
+//
+// Answers are checked against the answers file and reported as PASS, FAIL or NEW, `--record` stores
+// the current answers in it instead. Nothing is checked when the input is overridden.
+// `--record` also keeps the I/O of the Intcode programs fed on the fly (days 13 and 15) in
+// SESSIONS_DIR, `dayN_partP.txt` being the image the program started from and `dayN_partP.session`
+// its session, to replay with `intcode-replay`. A part whose
+// solver fails, like an Intcode program crashing on a wrong input, is reported as ERROR and counted
+// as failed.
+//
+// `--visualize` first plays the simulation of the days having one (11, 13, 15 and 17) in the terminal,
+// at up to `--fps` frames per second (60 by default). It can't be used with the JSON format.
+//
+// The JSON format prints an array with one object per day and part:
+//   {"day": 8, "part": 2, "input": "input_day8.txt", "status": "ok", "answer": {"text": "ACKPZ", "image": [" XX   XX  X  X XXX  XXXX", ...]}, "time_ms": 0.3}
+// Letters are given with the rows of the image they were read from, an image whose letters can't be
+// read is an error showing the unrecognised glyph.
+// `input` is null for embedded inputs and "<stdin>" for stdin. `status` is "ok", "mismatch" (with
+// the `expected` answer) or "error" (with an `error` message instead of the answer), a day failing
+// to load, parse or solve doesn't stop the other ones.
+
+use advent_of_code::animation::Recorder;
+use advent_of_code::answers::{AnswerFile, Verdict, ANSWERS_FILE};
+use advent_of_code::intcode::loader::{self, Format as ImageFormat};
+use advent_of_code::intcode::session::{Recording, Sessions};
+use advent_of_code::json;
+use advent_of_code::runner::{self, find_day, DAYS};
+use advent_of_code::solution::{self, SolveError};
+use advent_of_code::terminal::{self, Terminal};
+use std::fs;
+
+const SESSIONS_DIR: &str = "sessions";
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Format {
+    Text,
+    Json,
+}
+
+pub struct Options {
+    pub days: Vec<u32>,
+    pub part: Option<usize>,
+    pub input: Option<String>,
+    pub record: bool,
+    pub format: Format,
+    pub visualize: bool,
+    pub fps: u32,
+}
+
+impl Options {
+    pub fn parts(&self) -> Vec<usize> {
+        match self.part {
+            Some(part) => vec![part],
+            None => vec![1, 2],
+        }
+    }
+}
+
+fn parse_day(arg: &str) -> Result<u32, String> {
+    match arg.parse() {
+        Ok(number) if find_day(number).is_some() => Ok(number),
+        _ => Err(format!("unknown day `{}`", arg)),
+    }
+}
+
+pub fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        days: Vec::new(),
+        part: None,
+        input: None,
+        record: false,
+        format: Format::Text,
+        visualize: false,
+        fps: terminal::DEFAULT_FPS,
+    };
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--part" => {
+                options.part = match args.next().map(|s| s.as_str()) {
+                    Some("1") => Some(1),
+                    Some("2") => Some(2),
+                    Some(part) => return Err(format!("invalid part `{}`, expected 1 or 2", part)),
+                    None => return Err(String::from("--part needs a value")),
+                }
+            }
+            "--record" => options.record = true,
+            "--format" => {
+                options.format = match args.next().map(|s| s.as_str()) {
+                    Some("text") => Format::Text,
+                    Some("json") => Format::Json,
+                    Some(format) => return Err(format!("invalid format `{}`, expected text or json", format)),
+                    None => return Err(String::from("--format needs a value")),
+                }
+            }
+            "--visualize" => options.visualize = true,
+            "--fps" => {
+                options.fps = match args.next().map(|n| n.parse()) {
+                    Some(Ok(n)) if n > 0 => n,
+                    _ => return Err(String::from("--fps needs a positive number")),
+                }
+            }
+            "--input" => options.input = Some(args.next().ok_or_else(|| String::from("--input needs a path"))?.clone()),
+            "all" => options.days.extend(DAYS.iter().map(|day| day.number)),
+            arg if arg.starts_with("--") => return Err(format!("unknown option `{}`", arg)),
+            arg => match arg.find('-') {
+                Some(pos) => {
+                    let (first, last) = (parse_day(&arg[..pos])?, parse_day(&arg[pos + 1..])?);
+                    options.days.extend(DAYS.iter().map(|day| day.number).filter(|n| (first..=last).contains(n)));
+                }
+                None => options.days.push(parse_day(arg)?),
+            },
+        }
+    }
+
+    if options.days.is_empty() {
+        options.days.extend(DAYS.iter().map(|day| day.number));
+    }
+    options.days.sort_unstable();
+    options.days.dedup();
+
+    if options.visualize && options.format == Format::Json {
+        return Err(String::from("--visualize can't be used with --format json"));
+    }
+
+    if options.input.is_some() {
+        if options.days.len() != 1 {
+            return Err(String::from("--input can only be used when running a single day"));
+        }
+        if options.record {
+            return Err(String::from("--record can't be used with --input"));
+        }
+    }
+
+    Ok(options)
+}
+
+fn json_entry(day: u32, part: usize, input_path: Option<&str>, status: &str, details: &[(&str, Option<String>)]) -> String {
+    let mut fields = vec![
+        ("day", Some(day.to_string())),
+        ("part", Some(part.to_string())),
+        ("input", Some(input_path.map_or_else(|| String::from("null"), json::string))),
+        ("status", Some(json::string(status))),
+    ];
+    fields.extend_from_slice(details);
+    json::object(&fields)
+}
+
+// Saves the programs recorded while solving a part, numbered from 2 when it ran several ones
+fn save_recordings(day: u32, part: usize, recordings: &[Recording]) -> Result<(), String> {
+    if recordings.is_empty() {
+        return Ok(());
+    }
+    fs::create_dir_all(SESSIONS_DIR).map_err(|e| format!("{}: {}", SESSIONS_DIR, e))?;
+    for (i, recording) in recordings.iter().enumerate() {
+        let suffix = if i == 0 { String::new() } else { format!("_{}", i + 1) };
+        let name = format!("{}/day{}_part{}{}", SESSIONS_DIR, day, part, suffix);
+        let image_path = format!("{}.txt", name);
+        loader::save_file(&image_path, &recording.image, ImageFormat::Text).map_err(|e| format!("{}: {}", image_path, e))?;
+        recording.session.save_file(&format!("{}.session", name))?;
+    }
+    Ok(())
+}
+
+// Entry point for `run [days...] [--part N] [--input PATH] [--record] [--format text|json] [--visualize]
+// [--fps N]`, returns false when an answer doesn't match the recorded one or fails to be computed, or, in
+// JSON, when a day fails to load
+pub fn run_command(args: &[String]) -> Result<bool, String> {
+    let options = parse_args(args)?;
+    let mut answer_file = AnswerFile::load(ANSWERS_FILE)?;
+    let (mut passed, mut failed, mut new) = (0, 0, 0);
+    let mut sessions_recorded = 0;
+    let mut json_entries = Vec::new();
+
+    let parts = options.parts();
+
+    for number in &options.days {
+        let day = find_day(*number).unwrap();
+        let input_path = runner::input_path(day, options.input.as_deref());
+
+        let solve = |input: String| {
+            let with_path = |e: SolveError| e.with_path(input_path.unwrap_or("<embedded>")).to_string();
+            if options.visualize {
+                (day.animate)(&input, &mut Recorder::live(Terminal::new(options.fps))).map_err(with_path)?;
+            }
+            let mut sessions = if options.record { Sessions::new() } else { Sessions::disabled() };
+            (day.solve)(&input, &parts, &mut sessions).map_err(|e| with_path(e.into()))
+        };
+        let result = match runner::read_input(day, options.input.as_deref()).and_then(solve) {
+            Ok(result) => result,
+            Err(e) if options.format == Format::Json => {
+                failed += parts.len();
+                for &part in &parts {
+                    json_entries.push(json_entry(day.number, part, input_path, "error", &[("error", Some(json::string(&e)))]));
+                }
+                continue;
+            }
+            Err(e) => return Err(format!("Day {}: {}", day.number, e)),
+        };
+
+        for solution::PartResult { part, answer, time, recordings } in result.parts {
+            save_recordings(day.number, part, &recordings)?;
+            sessions_recorded += recordings.len();
+
+            let answer = match answer {
+                Ok(answer) => answer,
+                Err(e) => {
+                    failed += 1;
+                    let e = e.with_path(input_path.unwrap_or("<embedded>")).to_string();
+                    match options.format {
+                        Format::Json => json_entries.push(json_entry(day.number, part, input_path, "error", &[("error", Some(json::string(&e)))])),
+                        Format::Text => println!("Day {} part {} [ERROR]: {}", day.number, part, e),
+                    }
+                    continue;
+                }
+            };
+
+            let verdict = if options.input.is_some() { None } else { Some(answer_file.check(day.number, part, &answer)) };
+            match verdict {
+                Some(Verdict::Pass) => passed += 1,
+                Some(Verdict::Fail(_)) => failed += 1,
+                Some(Verdict::New) => new += 1,
+                None => {}
+            }
+
+            match (options.format, &verdict) {
+                (Format::Json, _) => {
+                    let (status, expected) = match &verdict {
+                        Some(Verdict::Fail(expected)) => ("mismatch", Some(expected)),
+                        _ => ("ok", None),
+                    };
+                    json_entries.push(json_entry(
+                        day.number,
+                        part,
+                        input_path,
+                        status,
+                        &[
+                            ("answer", Some(json::answer(&answer))),
+                            ("expected", expected.map(|e| json::expected(&answer, e))),
+                            ("time_ms", Some(format!("{:.3}", time.as_secs_f64() * 1000.0))),
+                        ],
+                    ));
+                }
+                (Format::Text, Some(verdict)) => println!("Day {} part {} [{}]: {}", day.number, part, verdict, answer),
+                (Format::Text, None) => println!("Day {} part {}: {}", day.number, part, answer),
+            }
+
+            if options.record {
+                answer_file.record(day.number, part, &answer);
+            }
+        }
+    }
+
+    if options.format == Format::Json {
+        println!("[\n{}\n]", json_entries.iter().map(|e| format!("  {}", e)).collect::<Vec<_>>().join(",\n"));
+    } else if options.input.is_none() {
+        println!("{} passed, {} failed, {} new", passed, failed, new);
+    }
+    if options.record {
+        answer_file.save(ANSWERS_FILE)?;
+        if options.format == Format::Text {
+            println!("Answers recorded in {}", ANSWERS_FILE);
+            if sessions_recorded > 0 {
+                println!("{} Intcode sessions recorded in {}", sessions_recorded, SESSIONS_DIR);
+            }
+        }
+        return Ok(true);
+    }
+
+    Ok(failed == 0)
+}
@@ -0,0 +1,178 @@
+// Pictures of the puzzles saved as image files.
+//
+// The format comes from the extension of the output: `.ppm` (binary P6), `.png` or `.svg`. Grids
+// become rasters through a palette giving the colour of every cell, each cell is drawn as a square
+// of `scale` pixels. PNG data is zlib compressed when the gzip feature is enabled and stored
+// uncompressed otherwise.
+
+use crate::grid::Grid;
+use crate::solution::Image;
+use std::fs;
+use std::path::Path;
+
+pub type Rgb = [u8; 3];
+
+pub const BLACK: Rgb = [0, 0, 0];
+pub const WHITE: Rgb = [255, 255, 255];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Raster {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<Rgb>,
+}
+
+impl Raster {
+    pub fn from_grid<T, F: Fn(&T) -> Rgb>(grid: &Grid<T>, palette: F) -> Raster {
+        Raster {
+            width: grid.width(),
+            height: grid.height(),
+            pixels: grid.iter().map(|(_, cell)| palette(cell)).collect(),
+        }
+    }
+
+    // Lit pixels in white on black
+    pub fn from_image(image: &Image) -> Raster {
+        Raster {
+            width: image.width,
+            height: image.height,
+            pixels: image.pixels.iter().map(|&lit| if lit { WHITE } else { BLACK }).collect(),
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> Rgb {
+        self.pixels[y * self.width + x]
+    }
+
+    // Every pixel becomes a `scale` by `scale` square
+    pub fn scaled(&self, scale: usize) -> Raster {
+        let (width, height) = (self.width * scale, self.height * scale);
+        let pixels = (0..height).flat_map(|y| (0..width).map(move |x| (x, y))).map(|(x, y)| self.get(x / scale, y / scale)).collect();
+        Raster { width, height, pixels }
+    }
+
+    pub fn to_ppm(&self) -> Vec<u8> {
+        let mut ppm = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        ppm.extend(self.pixels.iter().flatten());
+        ppm
+    }
+
+    pub fn to_png(&self) -> Vec<u8> {
+        let mut header = Vec::new();
+        header.extend_from_slice(&(self.width as u32).to_be_bytes());
+        header.extend_from_slice(&(self.height as u32).to_be_bytes());
+        // 8 bits RGB, default compression and filtering, no interlacing
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+        // Every row starts with its filter type, 0 for none
+        let mut data = Vec::with_capacity(self.height * (self.width * 3 + 1));
+        for row in self.pixels.chunks(self.width.max(1)).take(self.height) {
+            data.push(0);
+            data.extend(row.iter().flatten());
+        }
+
+        let mut png = vec![0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+        png_chunk(&mut png, b"IHDR", &header);
+        png_chunk(&mut png, b"IDAT", &zlib(&data));
+        png_chunk(&mut png, b"IEND", &[]);
+        png
+    }
+
+    // One rectangle per run of pixels of the same colour on a row, `scale` only sets the display size
+    pub fn to_svg(&self, scale: usize) -> String {
+        let mut svg = format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{}\" height=\"{}\" viewBox=\"0 0 {} {}\" shape-rendering=\"crispEdges\">\n",
+            self.width * scale,
+            self.height * scale,
+            self.width,
+            self.height
+        );
+
+        for y in 0..self.height {
+            let mut x = 0;
+            while x < self.width {
+                let colour = self.get(x, y);
+                let start = x;
+                while x < self.width && self.get(x, y) == colour {
+                    x += 1;
+                }
+                svg += &format!(
+                    "<rect x=\"{}\" y=\"{}\" width=\"{}\" height=\"1\" fill=\"#{:02x}{:02x}{:02x}\"/>\n",
+                    start,
+                    y,
+                    x - start,
+                    colour[0],
+                    colour[1],
+                    colour[2]
+                );
+            }
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    pub fn save(&self, path: &str, scale: usize) -> Result<(), String> {
+        let data = match Path::new(path).extension().and_then(|e| e.to_str()) {
+            Some("ppm") => self.scaled(scale).to_ppm(),
+            Some("png") => self.scaled(scale).to_png(),
+            Some("svg") => self.to_svg(scale).into_bytes(),
+            _ => return Err(format!("Unknown image format for {}, expected .ppm, .png or .svg", path)),
+        };
+        fs::write(path, data).map_err(|e| format!("Failed to write {}: {}", path, e))
+    }
+}
+
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0xedb8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+// Length, type, data and CRC of the type and data
+fn png_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+    let start = png.len();
+    png.extend_from_slice(kind);
+    png.extend_from_slice(data);
+    let crc = crc32(&png[start..]);
+    png.extend_from_slice(&crc.to_be_bytes());
+}
+
+#[cfg(feature = "gzip")]
+fn zlib(data: &[u8]) -> Vec<u8> {
+    use std::io::Write;
+
+    let mut encoder = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(data).and_then(|_| encoder.finish()).expect("Writing to memory can't fail")
+}
+
+// Stored deflate blocks of at most 65535 bytes, followed by the Adler-32 checksum
+#[cfg(not(feature = "gzip"))]
+fn zlib(data: &[u8]) -> Vec<u8> {
+    let mut zlib = vec![0x78, 0x01];
+    let mut blocks = data.chunks(0xffff).peekable();
+    if blocks.peek().is_none() {
+        zlib.extend_from_slice(&[1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let len = block.len() as u16;
+        zlib.push(if blocks.peek().is_none() { 1 } else { 0 });
+        zlib.extend_from_slice(&len.to_le_bytes());
+        zlib.extend_from_slice(&(!len).to_le_bytes());
+        zlib.extend_from_slice(block);
+    }
+
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in data {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    zlib.extend_from_slice(&((b << 16) | a).to_be_bytes());
+    zlib
+}
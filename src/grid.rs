@@ -0,0 +1,205 @@
+// 2D grids shared by the days working on maps.
+//
+// Points are (x, y) with y growing downwards. `Grid` is a dense rectangle with bounds checking,
+// `SparseGrid` grows in every direction and returns a default value for the cells never set.
+
+use crate::parse::{self, ParseError};
+use std::collections::HashMap;
+use std::ops::{Index, IndexMut};
+
+pub type Point = (i64, i64);
+
+// North, east, south and west
+pub const DIRECTIONS: [Point; 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+pub fn neighbours((x, y): Point) -> impl Iterator<Item = Point> {
+    DIRECTIONS.iter().map(move |(dx, dy)| (x + dx, y + dy))
+}
+
+// Smallest and largest coordinates of the points, None when there are none
+pub fn bounding_box<I: IntoIterator<Item = Point>>(points: I) -> Option<(Point, Point)> {
+    points.into_iter().fold(None, |bounds, (x, y)| match bounds {
+        None => Some(((x, y), (x, y))),
+        Some(((min_x, min_y), (max_x, max_y))) => Some(((min_x.min(x), min_y.min(y)), (max_x.max(x), max_y.max(y)))),
+    })
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T: Clone> Grid<T> {
+    pub fn new(width: usize, height: usize, fill: T) -> Grid<T> {
+        Grid {
+            width,
+            height,
+            cells: vec![fill; width * height],
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    // Rows must all have the same length
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Grid<T> {
+        let height = rows.len();
+        let width = rows.first().map_or(0, |row| row.len());
+        assert!(rows.iter().all(|row| row.len() == width), "Grid rows have different lengths");
+
+        Grid {
+            width,
+            height,
+            cells: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    // One row per line, `cell` returns None for the invalid characters
+    pub fn parse<F: Fn(char) -> Option<T>>(input: &str, cell: F) -> Result<Grid<T>, ParseError> {
+        parse::grid(input, cell).map(Grid::from_rows)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (x, y): Point) -> bool {
+        x >= 0 && y >= 0 && (x as usize) < self.width && (y as usize) < self.height
+    }
+
+    fn offset(&self, point: Point) -> Option<usize> {
+        if self.contains(point) {
+            Some(point.1 as usize * self.width + point.0 as usize)
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.offset(point).map(|offset| &self.cells[offset])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.offset(point).map(move |offset| &mut self.cells[offset])
+    }
+
+    // Neighbours inside the grid
+    pub fn neighbours(&self, point: Point) -> impl Iterator<Item = Point> + '_ {
+        neighbours(point).filter(move |p| self.contains(*p))
+    }
+
+    pub fn points(&self) -> impl Iterator<Item = Point> {
+        let width = self.width as i64;
+        (0..self.cells.len() as i64).map(move |i| (i % width, i / width))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(self.cells.iter())
+    }
+
+    // Bounding box of the cells matching `filter`
+    pub fn bounding_box<F: Fn(&T) -> bool>(&self, filter: F) -> Option<(Point, Point)> {
+        bounding_box(self.iter().filter(|(_, cell)| filter(cell)).map(|(point, _)| point))
+    }
+
+    pub fn map<U, F: Fn(&T) -> U>(&self, f: F) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    pub fn render<F: Fn(&T) -> char>(&self, cell: F) -> String {
+        let mut text = String::new();
+        for row in self.cells.chunks(self.width.max(1)) {
+            text.extend(row.iter().map(&cell));
+            text.push('\n');
+        }
+        text
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        match self.offset(point) {
+            Some(offset) => &self.cells[offset],
+            None => panic!("{:?} is outside of the {}x{} grid", point, self.width, self.height),
+        }
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        match self.offset(point) {
+            Some(offset) => &mut self.cells[offset],
+            None => panic!("{:?} is outside of the {}x{} grid", point, self.width, self.height),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SparseGrid<T> {
+    cells: HashMap<Point, T>,
+    default: T,
+}
+
+impl<T: Clone> SparseGrid<T> {
+    pub fn new(default: T) -> SparseGrid<T> {
+        SparseGrid { cells: HashMap::new(), default }
+    }
+
+    pub fn get(&self, point: Point) -> &T {
+        self.cells.get(&point).unwrap_or(&self.default)
+    }
+
+    // Sets the cell to the default value first when it was never set
+    pub fn get_mut(&mut self, point: Point) -> &mut T {
+        let default = &self.default;
+        self.cells.entry(point).or_insert_with(|| default.clone())
+    }
+
+    pub fn set(&mut self, point: Point, value: T) {
+        self.cells.insert(point, value);
+    }
+
+    // Number of cells set
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    // Cells set, in no particular order
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells.iter().map(|(point, cell)| (*point, cell))
+    }
+
+    // Bounding box of the cells set matching `filter`
+    pub fn bounding_box<F: Fn(&T) -> bool>(&self, filter: F) -> Option<(Point, Point)> {
+        bounding_box(self.iter().filter(|(_, cell)| filter(cell)).map(|(point, _)| point))
+    }
+
+    // Dense copy of the area between `min` and `max` included
+    pub fn to_grid(&self, (min, max): (Point, Point)) -> Grid<T> {
+        let rows = (min.1..=max.1).map(|y| (min.0..=max.0).map(|x| self.get((x, y)).clone()).collect()).collect();
+        Grid::from_rows(rows)
+    }
+
+    // Renders the bounding box of all the cells set, empty when there are none
+    pub fn render<F: Fn(&T) -> char>(&self, cell: F) -> String {
+        match self.bounding_box(|_| true) {
+            Some(bounds) => self.to_grid(bounds).render(cell),
+            None => String::new(),
+        }
+    }
+}
@@ -0,0 +1,332 @@
+// Static decoding and control flow analysis of Intcode images.
+//
+// Code is discovered by following execution from address 0 through fallthroughs and jumps with an
+// immediate target. Jumps through memory (typically function returns using the relative base) can't
+// be followed and are recorded as indirect. Their targets are guessed from the constants the program
+// stores (return addresses are stored before a call): those decoding as an instruction outside the
+// code already found are followed too.
+
+use crate::intcode::vm::ParamMode;
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Operation {
+    Add,
+    Mul,
+    Input,
+    Output,
+    JumpIfTrue,
+    JumpIfFalse,
+    LessThan,
+    Equal,
+    AdjustRelativeBase,
+    Halt,
+}
+
+impl Operation {
+    pub fn from_value(value: i64) -> Option<Operation> {
+        match value % 100 {
+            1 => Some(Operation::Add),
+            2 => Some(Operation::Mul),
+            3 => Some(Operation::Input),
+            4 => Some(Operation::Output),
+            5 => Some(Operation::JumpIfTrue),
+            6 => Some(Operation::JumpIfFalse),
+            7 => Some(Operation::LessThan),
+            8 => Some(Operation::Equal),
+            9 => Some(Operation::AdjustRelativeBase),
+            99 => Some(Operation::Halt),
+            _ => None,
+        }
+    }
+
+    pub fn code(self) -> i64 {
+        match self {
+            Operation::Add => 1,
+            Operation::Mul => 2,
+            Operation::Input => 3,
+            Operation::Output => 4,
+            Operation::JumpIfTrue => 5,
+            Operation::JumpIfFalse => 6,
+            Operation::LessThan => 7,
+            Operation::Equal => 8,
+            Operation::AdjustRelativeBase => 9,
+            Operation::Halt => 99,
+        }
+    }
+
+    pub fn num_params(self) -> usize {
+        match self {
+            Operation::Add | Operation::Mul | Operation::LessThan | Operation::Equal => 3,
+            Operation::JumpIfTrue | Operation::JumpIfFalse => 2,
+            Operation::Input | Operation::Output | Operation::AdjustRelativeBase => 1,
+            Operation::Halt => 0,
+        }
+    }
+
+    // Index (starting at 0) of the parameter the instruction writes to
+    pub fn write_param(self) -> Option<usize> {
+        match self {
+            Operation::Add | Operation::Mul | Operation::LessThan | Operation::Equal => Some(2),
+            Operation::Input => Some(0),
+            _ => None,
+        }
+    }
+
+    pub fn mnemonic(self) -> &'static str {
+        match self {
+            Operation::Add => "add",
+            Operation::Mul => "mul",
+            Operation::Input => "in",
+            Operation::Output => "out",
+            Operation::JumpIfTrue => "jnz",
+            Operation::JumpIfFalse => "jz",
+            Operation::LessThan => "lt",
+            Operation::Equal => "eq",
+            Operation::AdjustRelativeBase => "arb",
+            Operation::Halt => "halt",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Param {
+    pub mode: ParamMode,
+    pub value: i64,
+}
+
+impl Param {
+    pub fn immediate(&self) -> Option<i64> {
+        if self.mode == ParamMode::Immediate {
+            Some(self.value)
+        } else {
+            None
+        }
+    }
+
+    // Absolute address the parameter refers to, when known statically
+    pub fn position(&self) -> Option<usize> {
+        if self.mode == ParamMode::Position && self.value >= 0 {
+            Some(self.value as usize)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Instruction {
+    pub address: usize,
+    pub opcode: Operation,
+    pub params: Vec<Param>,
+}
+
+impl Instruction {
+    pub fn decode(memory: &[i64], address: usize) -> Option<Instruction> {
+        let value = *memory.get(address)?;
+        if value < 0 {
+            return None;
+        }
+        let opcode = Operation::from_value(value)?;
+
+        let mut modes = value / 100;
+        let mut params = Vec::new();
+        for i in 0..opcode.num_params() {
+            let mode = match modes % 10 {
+                0 => ParamMode::Position,
+                1 => ParamMode::Immediate,
+                2 => ParamMode::Relative,
+                _ => return None,
+            };
+            if mode == ParamMode::Immediate && opcode.write_param() == Some(i) {
+                return None;
+            }
+            params.push(Param {
+                mode,
+                value: memory.get(address + 1 + i).cloned().unwrap_or(0),
+            });
+            modes /= 10;
+        }
+
+        if modes != 0 {
+            return None;
+        }
+
+        Some(Instruction { address, opcode, params })
+    }
+
+    // Opcode and parameters, an instruction is never empty
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.params.len() + 1
+    }
+
+    pub fn next(&self) -> usize {
+        self.address + self.len()
+    }
+
+    pub fn encode(&self) -> Vec<i64> {
+        let mut op = self.opcode.code();
+        let mut factor = 100;
+        for param in &self.params {
+            op += param.mode.digit() * factor;
+            factor *= 10;
+        }
+
+        let mut words = vec![op];
+        words.extend(self.params.iter().map(|p| p.value));
+        words
+    }
+
+    pub fn is_jump(&self) -> bool {
+        self.opcode == Operation::JumpIfTrue || self.opcode == Operation::JumpIfFalse
+    }
+
+    // Some(true) when the jump is always taken, Some(false) when never taken
+    pub fn constant_condition(&self) -> Option<bool> {
+        let condition = self.params[0].immediate()?;
+        match self.opcode {
+            Operation::JumpIfTrue => Some(condition != 0),
+            Operation::JumpIfFalse => Some(condition == 0),
+            _ => None,
+        }
+    }
+
+    pub fn jump_target(&self) -> Option<usize> {
+        if !self.is_jump() {
+            return None;
+        }
+        self.params[1].immediate().filter(|t| *t >= 0).map(|t| t as usize)
+    }
+
+    pub fn falls_through(&self) -> bool {
+        self.opcode != Operation::Halt && self.constant_condition() != Some(true)
+    }
+
+    pub fn write_target(&self) -> Option<&Param> {
+        self.opcode.write_param().map(|i| &self.params[i])
+    }
+}
+
+#[derive(Default)]
+pub struct Cfg {
+    pub instructions: BTreeMap<usize, Instruction>,
+    pub block_starts: BTreeSet<usize>,
+    pub indirect_jumps: Vec<usize>,
+    pub invalid: Vec<usize>,
+    // Addresses written by a position mode parameter somewhere in the discovered code
+    pub static_writes: BTreeSet<usize>,
+    pub has_relative_writes: bool,
+}
+
+impl Cfg {
+    pub fn build(memory: &[i64]) -> Cfg {
+        let mut cfg = Cfg::default();
+        let mut queue = VecDeque::new();
+        queue.push_back(0);
+        cfg.block_starts.insert(0);
+
+        loop {
+            cfg.discover(memory, &mut queue);
+            if cfg.indirect_jumps.is_empty() {
+                break;
+            }
+            queue.extend(cfg.return_sites(memory));
+            if queue.is_empty() {
+                break;
+            }
+            cfg.block_starts.extend(queue.iter().copied());
+        }
+
+        cfg
+    }
+
+    // Follows execution from every queued address
+    fn discover(&mut self, memory: &[i64], queue: &mut VecDeque<usize>) {
+        while let Some(address) = queue.pop_front() {
+            if self.instructions.contains_key(&address) {
+                continue;
+            }
+
+            let inst = match Instruction::decode(memory, address) {
+                Some(inst) => inst,
+                None => {
+                    self.invalid.push(address);
+                    continue;
+                }
+            };
+
+            if let Some(target) = inst.write_target() {
+                match target.mode {
+                    ParamMode::Position => {
+                        if let Some(position) = target.position() {
+                            self.static_writes.insert(position);
+                        }
+                    }
+                    ParamMode::Relative => self.has_relative_writes = true,
+                    ParamMode::Immediate => {}
+                }
+            }
+
+            if inst.is_jump() && inst.constant_condition() != Some(false) {
+                match inst.jump_target() {
+                    Some(target) => {
+                        self.block_starts.insert(target);
+                        queue.push_back(target);
+                    }
+                    None => self.indirect_jumps.push(address),
+                }
+                self.block_starts.insert(inst.next());
+            }
+
+            if inst.falls_through() {
+                queue.push_back(inst.next());
+            }
+            self.instructions.insert(address, inst);
+        }
+    }
+
+    // Constants stored by the discovered code that may be the target of an indirect jump: addresses
+    // decoding as an instruction and not overlapping the code already found
+    fn return_sites(&self, memory: &[i64]) -> Vec<usize> {
+        let stored = self
+            .instructions
+            .values()
+            .filter(|inst| inst.opcode == Operation::Add)
+            .filter_map(|inst| match (inst.params[0].immediate(), inst.params[1].immediate()) {
+                (Some(value), Some(0)) | (Some(0), Some(value)) => Some(value),
+                _ => None,
+            });
+
+        let mut sites: Vec<usize> = stored
+            .filter(|&value| value >= 0)
+            .map(|value| value as usize)
+            .filter(|&address| self.instructions.range(..=address).next_back().is_none_or(|(_, i)| address >= i.next()))
+            .filter(|&address| !self.invalid.contains(&address) && Instruction::decode(memory, address).is_some())
+            .collect();
+        sites.sort_unstable();
+        sites.dedup();
+        sites
+    }
+
+    // Whether any word of the instruction may be overwritten by the program itself
+    pub fn is_self_modified(&self, inst: &Instruction) -> bool {
+        self.static_writes.range(inst.address..inst.next()).next().is_some()
+    }
+
+    // Instructions of the basic block starting at `start`, in order
+    pub fn block(&self, start: usize) -> Vec<&Instruction> {
+        let mut block = Vec::new();
+        let mut address = start;
+
+        while let Some(inst) = self.instructions.get(&address) {
+            block.push(inst);
+            if inst.is_jump() || inst.opcode == Operation::Halt || self.block_starts.contains(&inst.next()) {
+                break;
+            }
+            address = inst.next();
+        }
+
+        block
+    }
+}
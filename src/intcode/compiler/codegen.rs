@@ -0,0 +1,520 @@
+// Code generation from the syntax tree to an Intcode image.
+//
+// The relative base points to the frame of the running function:
+//   rb+0  return address
+//   rb+1  return value
+//   rb+2  parameters, then locals and expression temporaries sharing one stack of slots
+// A call copies the arguments past the end of the caller's frame, moves the relative base there
+// and jumps to the function, which returns by jumping through rb+0. The caller then moves the
+// relative base back and picks up the return value. Frame sizes are only known once a function is
+// generated, instructions depending on them are patched afterwards.
+// The stack starts right after the image, so compiled programs need the day 9 instruction set.
+
+use crate::intcode::cfg::Operation;
+use crate::intcode::compiler::ast::{BinaryOp, Expr, Function, Stmt, UnaryOp};
+use crate::intcode::compiler::lexer::{CompileError, Pos};
+use crate::intcode::compiler::parser;
+use crate::intcode::vm::ParamMode;
+use std::collections::HashMap;
+
+const RETURN_ADDRESS: i64 = 0;
+const RETURN_VALUE: i64 = 1;
+const FIRST_PARAM: i64 = 2;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Value {
+    Const(i64),
+    Label(usize),
+    // scale * frame size + offset, the frame size of the current function being unknown yet
+    Frame(i64, i64),
+}
+
+#[derive(Clone, Copy, PartialEq)]
+struct Operand {
+    mode: ParamMode,
+    value: Value,
+}
+
+fn imm(value: i64) -> Operand {
+    Operand {
+        mode: ParamMode::Immediate,
+        value: Value::Const(value),
+    }
+}
+
+fn slot(offset: i64) -> Operand {
+    Operand {
+        mode: ParamMode::Relative,
+        value: Value::Const(offset),
+    }
+}
+
+fn label(label: usize) -> Operand {
+    Operand {
+        mode: ParamMode::Immediate,
+        value: Value::Label(label),
+    }
+}
+
+// Slot `offset` of the frame following the current one
+fn next_frame(offset: i64) -> Operand {
+    Operand {
+        mode: ParamMode::Relative,
+        value: Value::Frame(1, offset),
+    }
+}
+
+fn constant(operand: Operand) -> Option<i64> {
+    match operand {
+        Operand {
+            mode: ParamMode::Immediate,
+            value: Value::Const(value),
+        } => Some(value),
+        _ => None,
+    }
+}
+
+struct Generator<'a> {
+    code: Vec<i64>,
+    labels: Vec<Option<usize>>,
+    label_fixups: Vec<(usize, usize)>,
+    frame_fixups: Vec<(usize, i64, i64)>,
+    functions: HashMap<&'a str, (usize, usize)>,
+    scopes: Vec<(i64, Vec<(&'a str, i64)>)>,
+    top: i64,
+    frame_size: i64,
+    loops: Vec<(usize, usize)>,
+}
+
+impl<'a> Generator<'a> {
+    fn new_label(&mut self) -> usize {
+        self.labels.push(None);
+        self.labels.len() - 1
+    }
+
+    fn place(&mut self, label: usize) {
+        self.labels[label] = Some(self.code.len());
+    }
+
+    fn emit(&mut self, operation: Operation, params: &[Operand]) {
+        let mut opcode = operation.code();
+        let mut factor = 100;
+        for param in params {
+            opcode += param.mode.digit() * factor;
+            factor *= 10;
+        }
+        self.code.push(opcode);
+
+        for param in params {
+            let word = match param.value {
+                Value::Const(value) => value,
+                Value::Label(label) => {
+                    self.label_fixups.push((self.code.len(), label));
+                    0
+                }
+                Value::Frame(scale, offset) => {
+                    self.frame_fixups.push((self.code.len(), scale, offset));
+                    0
+                }
+            };
+            self.code.push(word);
+        }
+    }
+
+    fn alloc(&mut self) -> i64 {
+        let slot = self.top;
+        self.top += 1;
+        self.frame_size = self.frame_size.max(self.top);
+        slot
+    }
+
+    fn mov(&mut self, source: Operand, destination: Operand) {
+        if source != destination {
+            self.emit(Operation::Add, &[source, imm(0), destination]);
+        }
+    }
+
+    fn jump(&mut self, target: usize) {
+        self.emit(Operation::JumpIfTrue, &[imm(1), label(target)]);
+    }
+
+    fn jump_if_false(&mut self, condition: Operand, target: usize) {
+        match constant(condition) {
+            Some(0) => self.jump(target),
+            Some(_) => {}
+            None => self.emit(Operation::JumpIfFalse, &[condition, label(target)]),
+        }
+    }
+
+    fn lookup(&self, name: &str, pos: Pos) -> Result<Operand, CompileError> {
+        for (_, variables) in self.scopes.iter().rev() {
+            if let Some((_, offset)) = variables.iter().rev().find(|(n, _)| *n == name) {
+                return Ok(slot(*offset));
+            }
+        }
+        Err(CompileError::new(pos, format!("undefined variable `{}`", name)))
+    }
+
+    fn push_scope(&mut self) {
+        self.scopes.push((self.top, Vec::new()));
+    }
+
+    fn pop_scope(&mut self) {
+        let (mark, _) = self.scopes.pop().unwrap();
+        self.top = mark;
+    }
+
+    fn function(&mut self, function: &'a Function) -> Result<(), CompileError> {
+        let (entry, _) = self.functions[function.name.as_str()];
+        self.place(entry);
+
+        self.top = FIRST_PARAM;
+        self.frame_size = FIRST_PARAM;
+        self.push_scope();
+        for param in &function.params {
+            let offset = self.alloc();
+            self.scopes.last_mut().unwrap().1.push((param, offset));
+        }
+        self.block(&function.body)?;
+        self.pop_scope();
+
+        // Implicit `return 0;`
+        self.mov(imm(0), slot(RETURN_VALUE));
+        self.emit(Operation::JumpIfTrue, &[imm(1), slot(RETURN_ADDRESS)]);
+
+        for (index, scale, offset) in self.frame_fixups.drain(..) {
+            self.code[index] = scale * self.frame_size + offset;
+        }
+        Ok(())
+    }
+
+    fn block(&mut self, statements: &'a [Stmt]) -> Result<(), CompileError> {
+        self.push_scope();
+        for statement in statements {
+            self.statement(statement)?;
+        }
+        self.pop_scope();
+        Ok(())
+    }
+
+    fn statement(&mut self, statement: &'a Stmt) -> Result<(), CompileError> {
+        let mark = self.top;
+
+        match statement {
+            Stmt::Let(name, value) => {
+                let value = self.expr(value)?;
+                self.top = mark;
+                let offset = self.alloc();
+                self.mov(value, slot(offset));
+                self.scopes.last_mut().unwrap().1.push((name, offset));
+                return Ok(());
+            }
+            Stmt::Assign(name, value, pos) => {
+                let variable = self.lookup(name, *pos)?;
+                let value = self.expr(value)?;
+                self.mov(value, variable);
+            }
+            Stmt::If(condition, then_block, else_block) => {
+                let else_label = self.new_label();
+                let condition = self.expr(condition)?;
+                self.top = mark;
+                self.jump_if_false(condition, else_label);
+                self.block(then_block)?;
+
+                if else_block.is_empty() {
+                    self.place(else_label);
+                } else {
+                    let end_label = self.new_label();
+                    self.jump(end_label);
+                    self.place(else_label);
+                    self.block(else_block)?;
+                    self.place(end_label);
+                }
+            }
+            Stmt::While(condition, body) => {
+                let start_label = self.new_label();
+                let end_label = self.new_label();
+                self.place(start_label);
+                let condition = self.expr(condition)?;
+                self.top = mark;
+                self.jump_if_false(condition, end_label);
+
+                self.loops.push((start_label, end_label));
+                self.block(body)?;
+                self.loops.pop();
+
+                self.jump(start_label);
+                self.place(end_label);
+            }
+            Stmt::Break(pos) => match self.loops.last() {
+                Some(&(_, end_label)) => self.jump(end_label),
+                None => return Err(CompileError::new(*pos, String::from("`break` outside of a loop"))),
+            },
+            Stmt::Continue(pos) => match self.loops.last() {
+                Some(&(start_label, _)) => self.jump(start_label),
+                None => return Err(CompileError::new(*pos, String::from("`continue` outside of a loop"))),
+            },
+            Stmt::Return(value) => {
+                let value = match value {
+                    Some(value) => self.expr(value)?,
+                    None => imm(0),
+                };
+                self.mov(value, slot(RETURN_VALUE));
+                self.emit(Operation::JumpIfTrue, &[imm(1), slot(RETURN_ADDRESS)]);
+            }
+            Stmt::Expr(expr) => {
+                self.expr(expr)?;
+            }
+        }
+
+        self.top = mark;
+        Ok(())
+    }
+
+    // Returns the operand holding the value, either a constant, a variable or a temporary slot
+    // above `top` when called
+    fn expr(&mut self, expr: &'a Expr) -> Result<Operand, CompileError> {
+        match expr {
+            Expr::Number(n) => Ok(imm(*n)),
+            Expr::Var(name, pos) => self.lookup(name, *pos),
+            Expr::Call(name, args, pos) => self.call(name, args, *pos),
+            Expr::Unary(op, operand) => {
+                let mark = self.top;
+                let value = self.expr(operand)?;
+                self.top = mark;
+
+                match (op, constant(value)) {
+                    (UnaryOp::Neg, Some(value)) => Ok(imm(value.wrapping_neg())),
+                    (UnaryOp::Not, Some(value)) => Ok(imm((value == 0) as i64)),
+                    (UnaryOp::Neg, None) => {
+                        let result = slot(self.alloc());
+                        self.emit(Operation::Mul, &[value, imm(-1), result]);
+                        Ok(result)
+                    }
+                    (UnaryOp::Not, None) => {
+                        let result = slot(self.alloc());
+                        self.emit(Operation::Equal, &[value, imm(0), result]);
+                        Ok(result)
+                    }
+                }
+            }
+            Expr::Binary(op @ BinaryOp::And, lhs, rhs) | Expr::Binary(op @ BinaryOp::Or, lhs, rhs) => self.logical(*op, lhs, rhs),
+            Expr::Binary(op, lhs, rhs) => self.binary(*op, lhs, rhs),
+        }
+    }
+
+    fn binary(&mut self, op: BinaryOp, lhs: &'a Expr, rhs: &'a Expr) -> Result<Operand, CompileError> {
+        let mark = self.top;
+        let a = self.expr(lhs)?;
+        let mut b = self.expr(rhs)?;
+
+        if let (Some(a), Some(b)) = (constant(a), constant(b)) {
+            let folded = match op {
+                BinaryOp::Add => a.checked_add(b),
+                BinaryOp::Sub => a.checked_sub(b),
+                BinaryOp::Mul => a.checked_mul(b),
+                BinaryOp::Eq => Some((a == b) as i64),
+                BinaryOp::Ne => Some((a != b) as i64),
+                BinaryOp::Lt => Some((a < b) as i64),
+                BinaryOp::Le => Some((a <= b) as i64),
+                BinaryOp::Gt => Some((a > b) as i64),
+                BinaryOp::Ge => Some((a >= b) as i64),
+                BinaryOp::And | BinaryOp::Or => None,
+            };
+            if let Some(folded) = folded {
+                self.top = mark;
+                return Ok(imm(folded));
+            }
+        }
+
+        // There is no subtraction, negate the right hand side into a slot above both operands first
+        if op == BinaryOp::Sub {
+            b = match constant(b) {
+                Some(value) => imm(value.wrapping_neg()),
+                None => {
+                    let negated = slot(self.alloc());
+                    self.emit(Operation::Mul, &[b, imm(-1), negated]);
+                    negated
+                }
+            };
+        }
+
+        // Instructions read their operands before writing, the result may reuse an operand's slot
+        self.top = mark;
+        let result = slot(self.alloc());
+        match op {
+            BinaryOp::Add | BinaryOp::Sub => self.emit(Operation::Add, &[a, b, result]),
+            BinaryOp::Mul => self.emit(Operation::Mul, &[a, b, result]),
+            BinaryOp::Eq => self.emit(Operation::Equal, &[a, b, result]),
+            BinaryOp::Lt => self.emit(Operation::LessThan, &[a, b, result]),
+            BinaryOp::Gt => self.emit(Operation::LessThan, &[b, a, result]),
+            BinaryOp::Ne => {
+                self.emit(Operation::Equal, &[a, b, result]);
+                self.emit(Operation::Equal, &[result, imm(0), result]);
+            }
+            BinaryOp::Le => {
+                self.emit(Operation::LessThan, &[b, a, result]);
+                self.emit(Operation::Equal, &[result, imm(0), result]);
+            }
+            BinaryOp::Ge => {
+                self.emit(Operation::LessThan, &[a, b, result]);
+                self.emit(Operation::Equal, &[result, imm(0), result]);
+            }
+            BinaryOp::And | BinaryOp::Or => unreachable!(),
+        }
+        Ok(result)
+    }
+
+    // Short circuiting `&&` and `||`, the result is always 0 or 1
+    fn logical(&mut self, op: BinaryOp, lhs: &'a Expr, rhs: &'a Expr) -> Result<Operand, CompileError> {
+        let mark = self.top;
+        let result = slot(self.alloc());
+        let end_label = self.new_label();
+
+        let a = self.expr(lhs)?;
+        self.top = mark + 1;
+        if op == BinaryOp::And {
+            self.mov(imm(0), result);
+            self.jump_if_false(a, end_label);
+        } else {
+            self.mov(imm(1), result);
+            match constant(a) {
+                Some(0) => {}
+                Some(_) => self.jump(end_label),
+                None => self.emit(Operation::JumpIfTrue, &[a, label(end_label)]),
+            }
+        }
+
+        let b = self.expr(rhs)?;
+        self.emit(Operation::Equal, &[b, imm(0), result]);
+        self.emit(Operation::Equal, &[result, imm(0), result]);
+        self.place(end_label);
+
+        self.top = mark + 1;
+        Ok(result)
+    }
+
+    fn call(&mut self, name: &'a str, args: &'a [Expr], pos: Pos) -> Result<Operand, CompileError> {
+        let mark = self.top;
+
+        match (name, args.len()) {
+            ("input", 0) => {
+                let result = slot(self.alloc());
+                self.emit(Operation::Input, &[result]);
+                return Ok(result);
+            }
+            ("output", 1) => {
+                let value = self.expr(&args[0])?;
+                self.emit(Operation::Output, &[value]);
+                self.top = mark;
+                return Ok(imm(0));
+            }
+            ("input", _) | ("output", _) => return Err(CompileError::new(pos, format!("wrong number of arguments for `{}`", name))),
+            _ => {}
+        }
+
+        let (entry, arity) = match self.functions.get(name) {
+            Some(&function) => function,
+            None => return Err(CompileError::new(pos, format!("undefined function `{}`", name))),
+        };
+        if args.len() != arity {
+            return Err(CompileError::new(pos, format!("`{}` takes {} arguments but {} were given", name, arity, args.len())));
+        }
+
+        // Arguments are all evaluated before being copied into the new frame, as evaluating one may
+        // involve another call using the same slots
+        let mut values = Vec::new();
+        for arg in args {
+            values.push(self.expr(arg)?);
+        }
+        for (i, value) in values.into_iter().enumerate() {
+            self.mov(value, next_frame(FIRST_PARAM + i as i64));
+        }
+
+        let return_label = self.new_label();
+        self.mov(label(return_label), next_frame(RETURN_ADDRESS));
+        self.emit(
+            Operation::AdjustRelativeBase,
+            &[Operand {
+                mode: ParamMode::Immediate,
+                value: Value::Frame(1, 0),
+            }],
+        );
+        self.jump(entry);
+        self.place(return_label);
+        self.emit(
+            Operation::AdjustRelativeBase,
+            &[Operand {
+                mode: ParamMode::Immediate,
+                value: Value::Frame(-1, 0),
+            }],
+        );
+
+        self.top = mark;
+        let result = slot(self.alloc());
+        self.mov(next_frame(RETURN_VALUE), result);
+        Ok(result)
+    }
+}
+
+pub fn generate(functions: &[Function]) -> Result<Vec<i64>, CompileError> {
+    let mut generator = Generator {
+        code: Vec::new(),
+        labels: Vec::new(),
+        label_fixups: Vec::new(),
+        frame_fixups: Vec::new(),
+        functions: HashMap::new(),
+        scopes: Vec::new(),
+        top: 0,
+        frame_size: 0,
+        loops: Vec::new(),
+    };
+
+    for function in functions {
+        if function.name == "input" || function.name == "output" {
+            return Err(CompileError::new(function.pos, format!("`{}` is a builtin function", function.name)));
+        }
+        let entry = generator.new_label();
+        if generator.functions.insert(&function.name, (entry, function.params.len())).is_some() {
+            return Err(CompileError::new(function.pos, format!("function `{}` is defined twice", function.name)));
+        }
+    }
+
+    let main = match functions.iter().find(|f| f.name == "main") {
+        Some(main) => main,
+        None => {
+            return Err(CompileError {
+                pos: None,
+                message: String::from("no `main` function"),
+            })
+        }
+    };
+    if !main.params.is_empty() {
+        return Err(CompileError::new(main.pos, String::from("`main` can't take parameters")));
+    }
+
+    // Sets up the stack after the image and calls main, which returns to the halt instruction
+    let stack_label = generator.new_label();
+    let halt_label = generator.new_label();
+    let (main_entry, _) = generator.functions["main"];
+    generator.emit(Operation::AdjustRelativeBase, &[label(stack_label)]);
+    generator.mov(label(halt_label), slot(RETURN_ADDRESS));
+    generator.jump(main_entry);
+    generator.place(halt_label);
+    generator.emit(Operation::Halt, &[]);
+
+    for function in functions {
+        generator.function(function)?;
+    }
+    generator.place(stack_label);
+
+    let mut code = generator.code;
+    for (index, label) in generator.label_fixups {
+        code[index] = generator.labels[label].unwrap() as i64;
+    }
+    Ok(code)
+}
+
+pub fn compile(source: &str) -> Result<Vec<i64>, CompileError> {
+    generate(&parser::parse(source)?)
+}
@@ -0,0 +1,206 @@
+// Tokenizer for the Intcode source language, `//` starts a comment running to the end of the line.
+
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Pos {
+    pub line: usize,
+    pub column: usize,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompileError {
+    pub pos: Option<Pos>,
+    pub message: String,
+}
+
+impl CompileError {
+    pub fn new(pos: Pos, message: String) -> CompileError {
+        CompileError { pos: Some(pos), message }
+    }
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.pos {
+            Some(pos) => write!(f, "{}:{}: {}", pos.line, pos.column, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+impl std::error::Error for CompileError {}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Token {
+    Number(i64),
+    Ident(String),
+    Fn,
+    Let,
+    If,
+    Else,
+    While,
+    Break,
+    Continue,
+    Return,
+    LParen,
+    RParen,
+    LBrace,
+    RBrace,
+    Comma,
+    Semicolon,
+    Assign,
+    Plus,
+    Minus,
+    Star,
+    Not,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+    Eof,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let text = match self {
+            Token::Number(n) => return write!(f, "`{}`", n),
+            Token::Ident(name) => return write!(f, "`{}`", name),
+            Token::Fn => "fn",
+            Token::Let => "let",
+            Token::If => "if",
+            Token::Else => "else",
+            Token::While => "while",
+            Token::Break => "break",
+            Token::Continue => "continue",
+            Token::Return => "return",
+            Token::LParen => "(",
+            Token::RParen => ")",
+            Token::LBrace => "{",
+            Token::RBrace => "}",
+            Token::Comma => ",",
+            Token::Semicolon => ";",
+            Token::Assign => "=",
+            Token::Plus => "+",
+            Token::Minus => "-",
+            Token::Star => "*",
+            Token::Not => "!",
+            Token::Eq => "==",
+            Token::Ne => "!=",
+            Token::Lt => "<",
+            Token::Le => "<=",
+            Token::Gt => ">",
+            Token::Ge => ">=",
+            Token::And => "&&",
+            Token::Or => "||",
+            Token::Eof => return write!(f, "end of file"),
+        };
+        write!(f, "`{}`", text)
+    }
+}
+
+fn keyword(word: &str) -> Option<Token> {
+    match word {
+        "fn" => Some(Token::Fn),
+        "let" => Some(Token::Let),
+        "if" => Some(Token::If),
+        "else" => Some(Token::Else),
+        "while" => Some(Token::While),
+        "break" => Some(Token::Break),
+        "continue" => Some(Token::Continue),
+        "return" => Some(Token::Return),
+        _ => None,
+    }
+}
+
+pub fn tokenize(source: &str) -> Result<Vec<(Token, Pos)>, CompileError> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    let mut pos = Pos { line: 1, column: 1 };
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = pos;
+        let next = chars.get(i + 1).cloned();
+
+        if c == '\n' {
+            i += 1;
+            pos.line += 1;
+            pos.column = 1;
+            continue;
+        }
+        if c.is_whitespace() {
+            i += 1;
+            pos.column += 1;
+            continue;
+        }
+        if c == '/' && next == Some('/') {
+            while i < chars.len() && chars[i] != '\n' {
+                i += 1;
+            }
+            continue;
+        }
+
+        let mut end = i + 1;
+        let token = if c.is_ascii_digit() {
+            while end < chars.len() && chars[end].is_ascii_digit() {
+                end += 1;
+            }
+            let text: String = chars[i..end].iter().collect();
+            match text.parse() {
+                Ok(n) => Token::Number(n),
+                Err(_) => return Err(CompileError::new(start, format!("number `{}` out of range", text))),
+            }
+        } else if c.is_alphabetic() || c == '_' {
+            while end < chars.len() && (chars[end].is_alphanumeric() || chars[end] == '_') {
+                end += 1;
+            }
+            let word: String = chars[i..end].iter().collect();
+            keyword(&word).unwrap_or(Token::Ident(word))
+        } else {
+            let two_chars = match (c, next) {
+                ('=', Some('=')) => Some(Token::Eq),
+                ('!', Some('=')) => Some(Token::Ne),
+                ('<', Some('=')) => Some(Token::Le),
+                ('>', Some('=')) => Some(Token::Ge),
+                ('&', Some('&')) => Some(Token::And),
+                ('|', Some('|')) => Some(Token::Or),
+                _ => None,
+            };
+            match two_chars {
+                Some(token) => {
+                    end += 1;
+                    token
+                }
+                None => match c {
+                    '(' => Token::LParen,
+                    ')' => Token::RParen,
+                    '{' => Token::LBrace,
+                    '}' => Token::RBrace,
+                    ',' => Token::Comma,
+                    ';' => Token::Semicolon,
+                    '=' => Token::Assign,
+                    '+' => Token::Plus,
+                    '-' => Token::Minus,
+                    '*' => Token::Star,
+                    '!' => Token::Not,
+                    '<' => Token::Lt,
+                    '>' => Token::Gt,
+                    _ => return Err(CompileError::new(start, format!("unexpected character `{}`", c))),
+                },
+            }
+        };
+
+        tokens.push((token, start));
+        pos.column += end - i;
+        i = end;
+    }
+
+    tokens.push((Token::Eof, pos));
+    Ok(tokens)
+}
@@ -0,0 +1,7 @@
+pub mod ast;
+pub mod codegen;
+pub mod lexer;
+pub mod parser;
+
+#[cfg(test)]
+mod tests;
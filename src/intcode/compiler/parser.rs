@@ -0,0 +1,241 @@
+// Recursive descent parser for the Intcode source language.
+//
+//   program    = function*
+//   function   = "fn" ident "(" [ident ("," ident)*] ")" block
+//   block      = "{" statement* "}"
+//   statement  = "let" ident "=" expr ";" | ident "=" expr ";" | "if" expr block ["else" (block | if)]
+//              | "while" expr block | "break" ";" | "continue" ";" | "return" [expr] ";" | expr ";"
+//   expr       = or, with `||` < `&&` < comparisons < `+ -` < `*` < unary `- !` in increasing precedence
+
+use crate::intcode::compiler::ast::{BinaryOp, Expr, Function, Stmt, UnaryOp};
+use crate::intcode::compiler::lexer::{tokenize, CompileError, Pos, Token};
+
+struct Parser {
+    tokens: Vec<(Token, Pos)>,
+    index: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.index].0
+    }
+
+    fn peek_at(&self, offset: usize) -> &Token {
+        let index = (self.index + offset).min(self.tokens.len() - 1);
+        &self.tokens[index].0
+    }
+
+    fn pos(&self) -> Pos {
+        self.tokens[self.index].1
+    }
+
+    fn advance(&mut self) -> Token {
+        let token = self.tokens[self.index].0.clone();
+        if self.index + 1 < self.tokens.len() {
+            self.index += 1;
+        }
+        token
+    }
+
+    fn unexpected(&self, expected: &str) -> CompileError {
+        CompileError::new(self.pos(), format!("expected {}, found {}", expected, self.peek()))
+    }
+
+    fn expect(&mut self, token: Token) -> Result<(), CompileError> {
+        if *self.peek() != token {
+            return Err(self.unexpected(&token.to_string()));
+        }
+        self.advance();
+        Ok(())
+    }
+
+    fn ident(&mut self) -> Result<String, CompileError> {
+        match self.peek().clone() {
+            Token::Ident(name) => {
+                self.advance();
+                Ok(name)
+            }
+            _ => Err(self.unexpected("a name")),
+        }
+    }
+
+    fn function(&mut self) -> Result<Function, CompileError> {
+        let pos = self.pos();
+        self.expect(Token::Fn)?;
+        let name = self.ident()?;
+
+        self.expect(Token::LParen)?;
+        let mut params = Vec::new();
+        if *self.peek() != Token::RParen {
+            params.push(self.ident()?);
+            while *self.peek() == Token::Comma {
+                self.advance();
+                params.push(self.ident()?);
+            }
+        }
+        self.expect(Token::RParen)?;
+
+        let body = self.block()?;
+        Ok(Function { name, params, body, pos })
+    }
+
+    fn block(&mut self) -> Result<Vec<Stmt>, CompileError> {
+        self.expect(Token::LBrace)?;
+        let mut statements = Vec::new();
+        while *self.peek() != Token::RBrace {
+            statements.push(self.statement()?);
+        }
+        self.advance();
+        Ok(statements)
+    }
+
+    fn statement(&mut self) -> Result<Stmt, CompileError> {
+        let pos = self.pos();
+        let stmt = match self.peek().clone() {
+            Token::Let => {
+                self.advance();
+                let name = self.ident()?;
+                self.expect(Token::Assign)?;
+                Stmt::Let(name, self.expr()?)
+            }
+            Token::Ident(name) if *self.peek_at(1) == Token::Assign => {
+                self.advance();
+                self.advance();
+                Stmt::Assign(name, self.expr()?, pos)
+            }
+            Token::If => return self.if_statement(),
+            Token::While => {
+                self.advance();
+                let condition = self.expr()?;
+                return Ok(Stmt::While(condition, self.block()?));
+            }
+            Token::Break => {
+                self.advance();
+                Stmt::Break(pos)
+            }
+            Token::Continue => {
+                self.advance();
+                Stmt::Continue(pos)
+            }
+            Token::Return => {
+                self.advance();
+                if *self.peek() == Token::Semicolon {
+                    Stmt::Return(None)
+                } else {
+                    Stmt::Return(Some(self.expr()?))
+                }
+            }
+            _ => Stmt::Expr(self.expr()?),
+        };
+
+        self.expect(Token::Semicolon)?;
+        Ok(stmt)
+    }
+
+    fn if_statement(&mut self) -> Result<Stmt, CompileError> {
+        self.expect(Token::If)?;
+        let condition = self.expr()?;
+        let then_block = self.block()?;
+
+        let else_block = if *self.peek() == Token::Else {
+            self.advance();
+            if *self.peek() == Token::If {
+                vec![self.if_statement()?]
+            } else {
+                self.block()?
+            }
+        } else {
+            Vec::new()
+        };
+
+        Ok(Stmt::If(condition, then_block, else_block))
+    }
+
+    fn expr(&mut self) -> Result<Expr, CompileError> {
+        self.binary(0)
+    }
+
+    // Binary operators by increasing precedence
+    fn binary(&mut self, level: usize) -> Result<Expr, CompileError> {
+        const LEVELS: [&[(Token, BinaryOp)]; 5] = [
+            &[(Token::Or, BinaryOp::Or)],
+            &[(Token::And, BinaryOp::And)],
+            &[
+                (Token::Eq, BinaryOp::Eq),
+                (Token::Ne, BinaryOp::Ne),
+                (Token::Lt, BinaryOp::Lt),
+                (Token::Le, BinaryOp::Le),
+                (Token::Gt, BinaryOp::Gt),
+                (Token::Ge, BinaryOp::Ge),
+            ],
+            &[(Token::Plus, BinaryOp::Add), (Token::Minus, BinaryOp::Sub)],
+            &[(Token::Star, BinaryOp::Mul)],
+        ];
+
+        if level == LEVELS.len() {
+            return self.unary();
+        }
+
+        let mut lhs = self.binary(level + 1)?;
+        while let Some((_, op)) = LEVELS[level].iter().find(|(token, _)| token == self.peek()) {
+            self.advance();
+            let rhs = self.binary(level + 1)?;
+            lhs = Expr::Binary(*op, Box::new(lhs), Box::new(rhs));
+        }
+        Ok(lhs)
+    }
+
+    fn unary(&mut self) -> Result<Expr, CompileError> {
+        let op = match self.peek() {
+            Token::Minus => UnaryOp::Neg,
+            Token::Not => UnaryOp::Not,
+            _ => return self.primary(),
+        };
+        self.advance();
+        Ok(Expr::Unary(op, Box::new(self.unary()?)))
+    }
+
+    fn primary(&mut self) -> Result<Expr, CompileError> {
+        let pos = self.pos();
+        match self.peek().clone() {
+            Token::Number(n) => {
+                self.advance();
+                Ok(Expr::Number(n))
+            }
+            Token::Ident(name) => {
+                self.advance();
+                if *self.peek() != Token::LParen {
+                    return Ok(Expr::Var(name, pos));
+                }
+
+                self.advance();
+                let mut args = Vec::new();
+                if *self.peek() != Token::RParen {
+                    args.push(self.expr()?);
+                    while *self.peek() == Token::Comma {
+                        self.advance();
+                        args.push(self.expr()?);
+                    }
+                }
+                self.expect(Token::RParen)?;
+                Ok(Expr::Call(name, args, pos))
+            }
+            Token::LParen => {
+                self.advance();
+                let expr = self.expr()?;
+                self.expect(Token::RParen)?;
+                Ok(expr)
+            }
+            _ => Err(self.unexpected("an expression")),
+        }
+    }
+}
+
+pub fn parse(source: &str) -> Result<Vec<Function>, CompileError> {
+    let mut parser = Parser { tokens: tokenize(source)?, index: 0 };
+    let mut functions = Vec::new();
+    while *parser.peek() != Token::Eof {
+        functions.push(parser.function()?);
+    }
+    Ok(functions)
+}
@@ -0,0 +1,255 @@
+// End to end tests, compiling programs and running them on the VM.
+
+use crate::intcode::compiler::codegen::compile;
+use crate::intcode::vm::{Program, RunState};
+
+fn run(source: &str, input: &[i64]) -> Vec<i64> {
+    let image = compile(source).unwrap_or_else(|e| panic!("{}", e));
+    let mut program = Program::new(&image);
+    assert_eq!(program.run_with_input(input), Ok(RunState::Halted));
+    program.take_output()
+}
+
+fn compile_error(source: &str) -> String {
+    match compile(source) {
+        Ok(_) => panic!("program compiled"),
+        Err(e) => e.to_string(),
+    }
+}
+
+#[test]
+fn empty_main_halts() {
+    assert_eq!(run("fn main() {}", &[]), Vec::<i64>::new());
+}
+
+#[test]
+fn arithmetic_and_precedence() {
+    let source = "
+        fn main() {
+            let a = input();
+            let b = input();
+            output(a + b * 2);
+            output((a + b) * 2);
+            output(a - b - 1);
+            output(-a * -b);
+            output(10 - 3 * 2 + -4);
+        }
+    ";
+    assert_eq!(run(source, &[7, 5]), vec![17, 24, 1, 35, 0]);
+}
+
+#[test]
+fn comparisons() {
+    let source = "
+        fn main() {
+            let a = input();
+            let b = input();
+            output(a == b);
+            output(a != b);
+            output(a < b);
+            output(a <= b);
+            output(a > b);
+            output(a >= b);
+            output(!a);
+        }
+    ";
+    assert_eq!(run(source, &[3, 4]), vec![0, 1, 1, 1, 0, 0, 0]);
+    assert_eq!(run(source, &[4, 4]), vec![1, 0, 0, 1, 0, 1, 0]);
+    assert_eq!(run(source, &[0, -1]), vec![0, 1, 0, 0, 1, 1, 1]);
+}
+
+#[test]
+fn logical_operators_short_circuit() {
+    let source = "
+        fn side_effect(value) {
+            output(100);
+            return value;
+        }
+
+        fn main() {
+            let a = input();
+            output(a && side_effect(5));
+            output(a || side_effect(0));
+            output(a > 0 && a < 10 || a == 42);
+        }
+    ";
+    assert_eq!(run(source, &[0]), vec![0, 100, 0, 0]);
+    assert_eq!(run(source, &[3]), vec![100, 1, 1, 1]);
+    assert_eq!(run(source, &[42]), vec![100, 1, 1, 1]);
+}
+
+#[test]
+fn if_else_chains() {
+    let source = "
+        fn sign(x) {
+            if x < 0 {
+                return -1;
+            } else if x == 0 {
+                return 0;
+            } else {
+                return 1;
+            }
+        }
+
+        fn main() {
+            output(sign(input()));
+            output(sign(input()));
+            output(sign(input()));
+        }
+    ";
+    assert_eq!(run(source, &[-20, 0, 7]), vec![-1, 0, 1]);
+}
+
+#[test]
+fn while_loops_with_break_and_continue() {
+    let source = "
+        fn main() {
+            let i = 0;
+            while 1 {
+                i = i + 1;
+                if i > 10 {
+                    break;
+                }
+                if i == 5 {
+                    continue;
+                }
+                let odd = 1;
+                let j = i;
+                while j > 0 {
+                    odd = !odd;
+                    j = j - 1;
+                }
+                if odd {
+                    continue;
+                }
+                output(i);
+            }
+        }
+    ";
+    assert_eq!(run(source, &[]), vec![1, 3, 7, 9]);
+}
+
+#[test]
+fn shadowing_and_scopes() {
+    let source = "
+        fn main() {
+            let x = 1;
+            if 1 {
+                let x = x + 10;
+                output(x);
+            }
+            let y = 2;
+            output(x);
+            output(y);
+            let x = x + y;
+            output(x);
+        }
+    ";
+    assert_eq!(run(source, &[]), vec![11, 1, 2, 3]);
+}
+
+#[test]
+fn recursion() {
+    let source = "
+        fn fib(n) {
+            if n < 2 {
+                return n;
+            }
+            return fib(n - 1) + fib(n - 2);
+        }
+
+        fn factorial(n) {
+            if n == 0 {
+                return 1;
+            }
+            return n * factorial(n - 1);
+        }
+
+        fn main() {
+            output(fib(input()));
+            output(factorial(input()));
+        }
+    ";
+    assert_eq!(run(source, &[15, 10]), vec![610, 3628800]);
+}
+
+#[test]
+fn nested_calls_as_arguments() {
+    let source = "
+        fn sub(a, b) {
+            return a - b;
+        }
+
+        fn mul3(a, b, c) {
+            return a * b * c;
+        }
+
+        fn main() {
+            output(sub(sub(10, 3), sub(5, sub(4, 2))));
+            output(mul3(sub(9, 7), 3, sub(1, mul3(1, 1, 1) + 1)));
+        }
+    ";
+    assert_eq!(run(source, &[]), vec![4, -6]);
+}
+
+#[test]
+fn implicit_and_empty_returns() {
+    let source = "
+        fn nothing() {}
+
+        fn early(x) {
+            if x {
+                return;
+            }
+            output(x);
+        }
+
+        fn main() {
+            output(nothing());
+            output(early(1));
+            early(0);
+        }
+    ";
+    assert_eq!(run(source, &[]), vec![0, 0, 0]);
+}
+
+#[test]
+fn echo_until_zero() {
+    let source = "
+        // Outputs each input until a zero is read
+        fn main() {
+            let value = input();
+            while value != 0 {
+                output(value);
+                value = input();
+            }
+        }
+    ";
+    assert_eq!(run(source, &[4, -2, 9, 0, 5]), vec![4, -2, 9]);
+}
+
+#[test]
+fn waits_for_missing_input() {
+    let image = compile("fn main() { output(input() + input()); }").unwrap();
+    let mut program = Program::new(&image);
+    assert_eq!(program.run_with_input(&[1]), Ok(RunState::NeedInput));
+    assert_eq!(program.run_with_input(&[2]), Ok(RunState::Halted));
+    assert_eq!(program.take_output(), vec![3]);
+}
+
+#[test]
+fn compile_errors() {
+    assert_eq!(compile_error("fn main() { output(x); }"), "1:20: undefined variable `x`");
+    assert_eq!(compile_error("fn main() {\n  foo();\n}"), "2:3: undefined function `foo`");
+    assert_eq!(compile_error("fn f(a) {}\nfn main() { f(1, 2); }"), "2:13: `f` takes 1 arguments but 2 were given");
+    assert_eq!(compile_error("fn main() { let x = 1 }"), "1:23: expected `;`, found `}`");
+    assert_eq!(compile_error("fn main() { break; }"), "1:13: `break` outside of a loop");
+    assert_eq!(compile_error("fn f() {}"), "no `main` function");
+    assert_eq!(compile_error("fn main(x) {}"), "1:1: `main` can't take parameters");
+    assert_eq!(compile_error("fn main() {}\nfn main() {}"), "2:1: function `main` is defined twice");
+    assert_eq!(compile_error("fn input() {}"), "1:1: `input` is a builtin function");
+    assert_eq!(compile_error("fn main() { input(1); }"), "1:13: wrong number of arguments for `input`");
+    assert_eq!(compile_error("fn main() { x = 1; }"), "1:13: undefined variable `x`");
+    assert_eq!(compile_error("fn main() { output(4 / 2); }"), "1:22: unexpected character `/`");
+    assert_eq!(compile_error("fn main() { let x = 99999999999999999999; }"), "1:21: number `99999999999999999999` out of range");
+}
@@ -0,0 +1,246 @@
+// Memory dumps of a running program, rendered as a hexdump style table.
+//
+// Each cell is shown with a two character marker, the first one tells whether the address was
+// executed as an opcode (X) or as an instruction parameter (o), the second one whether it was
+// written (W) or only read (r) as data at runtime.
+// Addresses that were never touched are guessed to be code when the value decodes as a valid
+// instruction, which helps telling dead code from initialised data.
+
+use crate::intcode::cfg::Instruction;
+use crate::intcode::vm::{Program, FLAG_EXECUTED, FLAG_OPERAND, FLAG_READ, FLAG_WRITTEN};
+use std::fmt::Write;
+use std::ops::Range;
+
+const VALUES_PER_ROW: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CellKind {
+    Code,
+    SelfModified,
+    Data,
+    LikelyCode,
+    Unknown,
+}
+
+impl CellKind {
+    fn name(self) -> &'static str {
+        match self {
+            CellKind::Code => "code",
+            CellKind::SelfModified => "self-modified code",
+            CellKind::Data => "data",
+            CellKind::LikelyCode => "unexecuted code?",
+            CellKind::Unknown => "unknown",
+        }
+    }
+}
+
+// Length of the instruction if the value is an opcode with valid modes for every parameter
+// and no immediate mode for written parameters
+pub fn instruction_length(value: i64) -> Option<usize> {
+    Instruction::decode(&[value], 0).map(|inst| inst.len())
+}
+
+fn ascii(value: i64) -> char {
+    if (32..127).contains(&value) {
+        value as u8 as char
+    } else {
+        '.'
+    }
+}
+
+#[derive(Clone)]
+pub struct MemoryDump {
+    pub memory: Vec<i64>,
+    pub flags: Vec<u8>,
+    pub ip: usize,
+    pub relative_base: i64,
+}
+
+pub struct Change {
+    pub address: usize,
+    pub before: i64,
+    pub after: i64,
+}
+
+impl MemoryDump {
+    pub fn capture(program: &Program) -> MemoryDump {
+        MemoryDump {
+            memory: program.memory().to_vec(),
+            flags: program.flags().to_vec(),
+            ip: program.ip(),
+            relative_base: program.relative_base(),
+        }
+    }
+
+    pub fn read(&self, address: usize) -> i64 {
+        self.memory.get(address).cloned().unwrap_or(0)
+    }
+
+    fn flags_at(&self, address: usize) -> u8 {
+        self.flags.get(address).cloned().unwrap_or(0)
+    }
+
+    fn classify_flags(&self, address: usize) -> CellKind {
+        let flags = self.flags_at(address);
+        let executed = flags & (FLAG_EXECUTED | FLAG_OPERAND) != 0;
+
+        if executed && flags & FLAG_WRITTEN != 0 {
+            CellKind::SelfModified
+        } else if executed {
+            CellKind::Code
+        } else if flags & (FLAG_READ | FLAG_WRITTEN) != 0 {
+            CellKind::Data
+        } else {
+            CellKind::Unknown
+        }
+    }
+
+    // Untouched addresses are swept linearly, a whole instruction is guessed to be code when its
+    // opcode decodes and none of its parameters were used as data
+    pub fn classify_all(&self) -> Vec<CellKind> {
+        let mut kinds: Vec<CellKind> = (0..self.memory.len()).map(|address| self.classify_flags(address)).collect();
+
+        let mut address = 0;
+        while address < kinds.len() {
+            if kinds[address] == CellKind::Unknown {
+                if let Some(length) = instruction_length(self.read(address)) {
+                    let end = address + length;
+                    if end <= kinds.len() && kinds[address..end].iter().all(|k| *k == CellKind::Unknown) {
+                        kinds[address..end].iter_mut().for_each(|k| *k = CellKind::LikelyCode);
+                        address = end;
+                        continue;
+                    }
+                }
+            }
+            address += 1;
+        }
+
+        kinds
+    }
+
+    pub fn classify(&self, address: usize) -> CellKind {
+        self.classify_all().get(address).cloned().unwrap_or(CellKind::Unknown)
+    }
+
+    fn marker(&self, address: usize) -> String {
+        let flags = self.flags_at(address);
+        let mut marker = String::new();
+
+        marker.push(if flags & FLAG_EXECUTED != 0 {
+            'X'
+        } else if flags & FLAG_OPERAND != 0 {
+            'o'
+        } else {
+            ' '
+        });
+        marker.push(if flags & FLAG_WRITTEN != 0 {
+            'W'
+        } else if flags & FLAG_READ != 0 {
+            'r'
+        } else {
+            ' '
+        });
+
+        marker
+    }
+
+    // Contiguous runs of addresses of the same kind, useful to locate buffers and counters
+    pub fn regions(&self) -> Vec<(Range<usize>, CellKind)> {
+        let mut regions: Vec<(Range<usize>, CellKind)> = Vec::new();
+
+        for (address, kind) in self.classify_all().into_iter().enumerate() {
+            match regions.last_mut() {
+                Some((range, last_kind)) if *last_kind == kind => range.end = address + 1,
+                _ => regions.push((address..address + 1, kind)),
+            }
+        }
+
+        regions
+    }
+
+    pub fn find_value(&self, value: i64) -> Vec<usize> {
+        self.memory.iter().enumerate().filter(|(_, v)| **v == value).map(|(address, _)| address).collect()
+    }
+
+    pub fn render(&self, range: Range<usize>) -> String {
+        let mut out = String::new();
+        let end = range.end.min(self.memory.len());
+        let start = range.start - (range.start % VALUES_PER_ROW);
+
+        writeln!(out, "ip: {} relative base: {}", self.ip, self.relative_base).unwrap();
+        write!(out, " address |").unwrap();
+        for column in 0..VALUES_PER_ROW {
+            write!(out, " {:>13}", format!("+{}", column)).unwrap();
+        }
+        writeln!(out, " | ascii").unwrap();
+
+        for row_start in (start..end).step_by(VALUES_PER_ROW) {
+            let mut text = String::new();
+            write!(out, "{:>8} |", row_start).unwrap();
+            for address in row_start..row_start + VALUES_PER_ROW {
+                if address < range.start || address >= end {
+                    write!(out, " {:>13}", "").unwrap();
+                    text.push(' ');
+                } else {
+                    write!(out, " {:>11}{}", self.read(address), self.marker(address)).unwrap();
+                    text.push(ascii(self.read(address)));
+                }
+            }
+            writeln!(out, " | {}", text).unwrap();
+        }
+
+        out
+    }
+
+    pub fn render_regions(&self) -> String {
+        let mut out = String::new();
+        for (range, kind) in self.regions() {
+            writeln!(out, "{:>8}-{:<8} {:>6} values  {}", range.start, range.end - 1, range.len(), kind.name()).unwrap();
+        }
+        out
+    }
+
+    pub fn changes(&self, later: &MemoryDump) -> Vec<Change> {
+        let len = self.memory.len().max(later.memory.len());
+        (0..len)
+            .filter(|address| self.read(*address) != later.read(*address))
+            .map(|address| Change {
+                address,
+                before: self.read(address),
+                after: later.read(address),
+            })
+            .collect()
+    }
+
+    pub fn render_diff(&self, later: &MemoryDump) -> String {
+        let mut out = String::new();
+        let changes = self.changes(later);
+        let kinds = later.classify_all();
+
+        writeln!(
+            out,
+            "{} addresses changed, ip {} -> {}, relative base {} -> {}",
+            changes.len(),
+            self.ip,
+            later.ip,
+            self.relative_base,
+            later.relative_base
+        )
+        .unwrap();
+        for change in &changes {
+            writeln!(
+                out,
+                "{:>8} | {:>12} -> {:<12} {} -> {} | {}",
+                change.address,
+                change.before,
+                change.after,
+                ascii(change.before),
+                ascii(change.after),
+                kinds.get(change.address).cloned().unwrap_or(CellKind::Unknown).name()
+            )
+            .unwrap();
+        }
+
+        out
+    }
+}
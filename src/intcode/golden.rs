@@ -0,0 +1,232 @@
+// Headless regression runner checking a program against a directory of golden `.case` files.
+//
+// A case file is made of `key: value` lines, `#` starts a comment:
+//   input: 1, 2       values fed to the program, in order (key may be repeated)
+//   output: 3 4 5     exact list of values the program must output (key may be repeated)
+//   state: halted     state the program must stop in, `halted` (default) or `input`
+//   max_steps: 1000   instruction budget before the case is considered hung (default 10000000)
+//   profile: day5     instruction set profile the program is strictly limited to (default day9)
+
+use crate::intcode::loader;
+use crate::intcode::profile::Profile;
+use crate::intcode::vm::{Program, RunState};
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const DEFAULT_MAX_STEPS: u64 = 10_000_000;
+
+pub struct Case {
+    pub name: String,
+    pub input: Vec<i64>,
+    pub expected_output: Vec<i64>,
+    pub expected_state: RunState,
+    pub max_steps: u64,
+    pub profile: Profile,
+}
+
+pub enum Outcome {
+    Pass,
+    Fail(Vec<String>),
+}
+
+impl Case {
+    pub fn parse(name: &str, text: &str) -> Result<Case, String> {
+        let mut case = Case {
+            name: name.to_string(),
+            input: Vec::new(),
+            expected_output: Vec::new(),
+            expected_state: RunState::Halted,
+            max_steps: DEFAULT_MAX_STEPS,
+            profile: Profile::Day9,
+        };
+
+        for (line_index, line) in text.lines().enumerate() {
+            let line = match line.find('#') {
+                Some(pos) => &line[..pos],
+                None => line,
+            };
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let err = |msg: String| format!("{}:{}: {}", name, line_index + 1, msg);
+
+            let (key, value) = match line.find(':') {
+                Some(pos) => (line[..pos].trim(), line[pos + 1..].trim()),
+                None => return Err(err(String::from("expected `key: value`"))),
+            };
+
+            match key {
+                "input" => case.input.extend(loader::parse_text(value).map_err(|e| err(e.to_string()))?),
+                "output" => case.expected_output.extend(loader::parse_text(value).map_err(|e| err(e.to_string()))?),
+                "state" => {
+                    case.expected_state = match value {
+                        "halted" => RunState::Halted,
+                        "input" => RunState::NeedInput,
+                        s => return Err(err(format!("unknown state `{}`", s))),
+                    }
+                }
+                "max_steps" => case.max_steps = value.parse().map_err(|_| err(format!("invalid step count `{}`", value)))?,
+                "profile" => case.profile = Profile::from_name(value).ok_or_else(|| err(format!("unknown profile `{}`", value)))?,
+                k => return Err(err(format!("unknown key `{}`", k))),
+            }
+        }
+
+        Ok(case)
+    }
+
+    pub fn run(&self, image: &[i64]) -> Outcome {
+        let mut program = Program::with_profile(image, self.profile, true);
+        program.extend_input(self.input.iter().cloned());
+
+        let mut steps = 0;
+        let state = loop {
+            if steps == self.max_steps {
+                break None;
+            }
+            steps += 1;
+
+            match program.step() {
+                Ok(None) => {}
+                Ok(Some(state)) => break Some(state),
+                Err(e) => {
+                    let mut failures = vec![format!("vm error: {}", e)];
+                    failures.extend(diff_output(&self.expected_output, program.output().iter().cloned().collect()));
+                    return Outcome::Fail(failures);
+                }
+            }
+        };
+
+        let output: Vec<i64> = program.output().iter().cloned().collect();
+        let mut failures = diff_output(&self.expected_output, output);
+
+        match state {
+            None => failures.push(format!("no halt or input wait after {} steps", self.max_steps)),
+            Some(state) if state != self.expected_state => failures.push(format!("state: expected {}, got {}", self.expected_state, state)),
+            _ => {}
+        }
+
+        if failures.is_empty() {
+            Outcome::Pass
+        } else {
+            Outcome::Fail(failures)
+        }
+    }
+}
+
+fn format_values(values: &[i64]) -> String {
+    values.iter().map(|v| v.to_string()).collect::<Vec<String>>().join(",")
+}
+
+fn diff_output(expected: &[i64], actual: Vec<i64>) -> Vec<String> {
+    if expected == actual.as_slice() {
+        return Vec::new();
+    }
+
+    let mut lines = Vec::new();
+    match expected.iter().zip(actual.iter()).position(|(e, a)| e != a) {
+        Some(index) => lines.push(format!("output differs at index {}: expected {}, got {}", index, expected[index], actual[index])),
+        None => lines.push(format!("output length: expected {} values, got {}", expected.len(), actual.len())),
+    }
+    lines.push(format!("  expected: [{}]", format_values(expected)));
+    lines.push(format!("  actual:   [{}]", format_values(&actual)));
+    lines
+}
+
+pub struct SuiteResult {
+    pub results: Vec<(String, Outcome)>,
+}
+
+impl SuiteResult {
+    pub fn num_failed(&self) -> usize {
+        self.results.iter().filter(|(_, outcome)| matches!(outcome, Outcome::Fail(_))).count()
+    }
+}
+
+impl fmt::Display for SuiteResult {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, outcome) in &self.results {
+            match outcome {
+                Outcome::Pass => writeln!(f, "PASS {}", name)?,
+                Outcome::Fail(failures) => {
+                    writeln!(f, "FAIL {}", name)?;
+                    for line in failures {
+                        writeln!(f, "  {}", line)?;
+                    }
+                }
+            }
+        }
+        let failed = self.num_failed();
+        write!(f, "{} passed, {} failed", self.results.len() - failed, failed)
+    }
+}
+
+pub fn load_cases(dir: &Path) -> Result<Vec<Case>, String> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .map_err(|e| format!("{}: {}", dir.display(), e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "case"))
+        .collect();
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| {
+            let name = path.file_stem().unwrap().to_string_lossy().to_string();
+            let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+            Case::parse(&name, &text)
+        })
+        .collect()
+}
+
+pub fn run_suite(image: &[i64], cases: &[Case]) -> SuiteResult {
+    SuiteResult {
+        results: cases.iter().map(|case| (case.name.clone(), case.run(image))).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_suite(day: u32, num_cases: usize) {
+        let image = loader::load_file(format!("input_day{}.txt", day)).unwrap();
+        let cases = load_cases(Path::new(&format!("golden/day{}", day))).unwrap();
+        assert_eq!(cases.len(), num_cases);
+        let result = run_suite(&image, &cases);
+        assert_eq!(result.num_failed(), 0, "\n{}", result);
+    }
+
+    #[test]
+    fn day2_suite() {
+        check_suite(2, 1);
+    }
+
+    #[test]
+    fn day5_suite() {
+        check_suite(5, 3);
+    }
+
+    #[test]
+    fn day9_suite() {
+        check_suite(9, 2);
+    }
+
+    #[test]
+    fn failures_are_reported() {
+        let case = Case::parse("echo", "input: 5\noutput: 5, 6\nstate: input").unwrap();
+        let result = run_suite(&[3, 0, 4, 0, 99], &[case]);
+        assert_eq!(
+            result.to_string(),
+            "FAIL echo\n  output length: expected 2 values, got 1\n    expected: [5,6]\n    actual:   [5]\n  state: expected waiting for input, got halted\n0 passed, 1 failed"
+        );
+    }
+
+    #[test]
+    fn invalid_cases() {
+        assert_eq!(Case::parse("c", "input: 1\nspeed: 3").err(), Some("c:2: unknown key `speed`".to_string()));
+        assert_eq!(Case::parse("c", "state: running").err(), Some("c:1: unknown state `running`".to_string()));
+        assert_eq!(Case::parse("c", "output 1").err(), Some("c:1: expected `key: value`".to_string()));
+    }
+}
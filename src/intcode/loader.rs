@@ -0,0 +1,352 @@
+// Loading and saving of Intcode images.
+//
+// Text images are integers separated by commas and/or any whitespace, empty entries are ignored
+// and `#` starts a comment running to the end of the line.
+// Binary images start with BINARY_MAGIC followed by the number of values and the values themselves,
+// all stored as zigzag encoded LEB128 varints.
+// Both formats may be gzip compressed, this is detected from the content when loading and from a
+// `.gz` extension when saving.
+
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read, Write};
+use std::path::{Path, PathBuf};
+
+pub const BINARY_MAGIC: &[u8; 4] = b"ICB\x01";
+const GZIP_MAGIC: &[u8; 2] = b"\x1f\x8b";
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Text,
+    Binary,
+}
+
+#[derive(Debug)]
+pub enum LoadErrorKind {
+    Io(io::Error),
+    Parse { line: usize, column: usize, token: String },
+    Binary(String),
+    GzipUnsupported,
+}
+
+#[derive(Debug)]
+pub struct LoadError {
+    pub path: Option<PathBuf>,
+    pub kind: LoadErrorKind,
+}
+
+impl LoadError {
+    fn new(kind: LoadErrorKind) -> LoadError {
+        LoadError { path: None, kind }
+    }
+
+    fn with_path(mut self, path: &Path) -> LoadError {
+        self.path = Some(path.to_path_buf());
+        self
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let source = match &self.path {
+            Some(path) => path.display().to_string(),
+            None => String::from("<memory>"),
+        };
+
+        match &self.kind {
+            LoadErrorKind::Io(e) => write!(f, "{}: {}", source, e),
+            LoadErrorKind::Parse { line, column, token } => write!(f, "{}:{}:{}: invalid value `{}`", source, line, column, token),
+            LoadErrorKind::Binary(msg) => write!(f, "{}: invalid binary image: {}", source, msg),
+            LoadErrorKind::GzipUnsupported => write!(f, "{}: gzip support was not compiled in", source),
+        }
+    }
+}
+
+impl std::error::Error for LoadError {}
+
+impl From<io::Error> for LoadError {
+    fn from(e: io::Error) -> LoadError {
+        LoadError::new(LoadErrorKind::Io(e))
+    }
+}
+
+pub fn parse_text(text: &str) -> Result<Vec<i64>, LoadError> {
+    let mut values = Vec::new();
+
+    for (line_index, line) in text.lines().enumerate() {
+        let line = match line.find('#') {
+            Some(pos) => &line[..pos],
+            None => line,
+        };
+
+        // Byte offset and column (counted in characters) of the current token
+        let mut token_start = None;
+        // Extra separator at the end so the last token is always flushed
+        for (column, (offset, c)) in line.char_indices().chain(std::iter::once((line.len(), ','))).enumerate() {
+            if c == ',' || c.is_whitespace() {
+                if let Some((start, start_column)) = token_start.take() {
+                    let token = &line[start..offset];
+                    let value = token.parse().map_err(|_| {
+                        LoadError::new(LoadErrorKind::Parse {
+                            line: line_index + 1,
+                            column: start_column + 1,
+                            token: token.to_string(),
+                        })
+                    })?;
+                    values.push(value);
+                }
+            } else if token_start.is_none() {
+                token_start = Some((offset, column));
+            }
+        }
+    }
+
+    Ok(values)
+}
+
+fn read_varint(bytes: &[u8], pos: &mut usize) -> Result<u64, LoadError> {
+    let mut result = 0u64;
+    let mut shift = 0;
+
+    loop {
+        let byte = match bytes.get(*pos) {
+            Some(b) => *b,
+            None => return Err(LoadError::new(LoadErrorKind::Binary(format!("truncated varint at offset {}", *pos)))),
+        };
+        *pos += 1;
+
+        if shift >= 64 || (shift == 63 && byte & 0x7e != 0) {
+            return Err(LoadError::new(LoadErrorKind::Binary(format!("varint overflow at offset {}", *pos - 1))));
+        }
+        result |= u64::from(byte & 0x7f) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(result);
+        }
+        shift += 7;
+    }
+}
+
+fn write_varint(out: &mut Vec<u8>, mut value: u64) {
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn zigzag_encode(value: i64) -> u64 {
+    ((value << 1) ^ (value >> 63)) as u64
+}
+
+fn zigzag_decode(value: u64) -> i64 {
+    ((value >> 1) as i64) ^ -((value & 1) as i64)
+}
+
+pub fn parse_binary(bytes: &[u8]) -> Result<Vec<i64>, LoadError> {
+    if !bytes.starts_with(BINARY_MAGIC) {
+        return Err(LoadError::new(LoadErrorKind::Binary(String::from("missing magic"))));
+    }
+
+    let mut pos = BINARY_MAGIC.len();
+    let count = read_varint(bytes, &mut pos)? as usize;
+
+    // Every value takes at least one byte, don't trust the count for the allocation
+    let mut values = Vec::with_capacity(count.min(bytes.len() - pos));
+    for _ in 0..count {
+        values.push(zigzag_decode(read_varint(bytes, &mut pos)?));
+    }
+
+    if pos != bytes.len() {
+        return Err(LoadError::new(LoadErrorKind::Binary(format!("{} trailing bytes", bytes.len() - pos))));
+    }
+
+    Ok(values)
+}
+
+#[cfg(feature = "gzip")]
+fn gunzip(bytes: &[u8]) -> Result<Vec<u8>, LoadError> {
+    let mut decoded = Vec::new();
+    flate2::read::GzDecoder::new(bytes).read_to_end(&mut decoded)?;
+    Ok(decoded)
+}
+
+#[cfg(not(feature = "gzip"))]
+fn gunzip(_bytes: &[u8]) -> Result<Vec<u8>, LoadError> {
+    Err(LoadError::new(LoadErrorKind::GzipUnsupported))
+}
+
+pub fn parse_bytes(bytes: &[u8]) -> Result<Vec<i64>, LoadError> {
+    if bytes.starts_with(GZIP_MAGIC) {
+        return parse_bytes(&gunzip(bytes)?);
+    }
+
+    if bytes.starts_with(BINARY_MAGIC) {
+        return parse_binary(bytes);
+    }
+
+    match std::str::from_utf8(bytes) {
+        Ok(text) => parse_text(text),
+        Err(e) => Err(LoadError::new(LoadErrorKind::Io(io::Error::new(io::ErrorKind::InvalidData, e)))),
+    }
+}
+
+pub fn load<R: Read>(mut reader: R) -> Result<Vec<i64>, LoadError> {
+    let mut bytes = Vec::new();
+    reader.read_to_end(&mut bytes)?;
+    parse_bytes(&bytes)
+}
+
+pub fn load_file<P: AsRef<Path>>(path: P) -> Result<Vec<i64>, LoadError> {
+    let path = path.as_ref();
+    File::open(path).map_err(LoadError::from).and_then(load).map_err(|e| e.with_path(path))
+}
+
+pub fn to_text(memory: &[i64]) -> String {
+    let mut text: String = memory.iter().map(|v| v.to_string()).collect::<Vec<String>>().join(",");
+    text.push('\n');
+    text
+}
+
+pub fn to_binary(memory: &[i64]) -> Vec<u8> {
+    let mut out = BINARY_MAGIC.to_vec();
+    write_varint(&mut out, memory.len() as u64);
+    for v in memory {
+        write_varint(&mut out, zigzag_encode(*v));
+    }
+    out
+}
+
+pub fn save<W: Write>(mut writer: W, memory: &[i64], format: Format) -> io::Result<()> {
+    match format {
+        Format::Text => writer.write_all(to_text(memory).as_bytes()),
+        Format::Binary => writer.write_all(&to_binary(memory)),
+    }
+}
+
+fn gzip_unsupported() -> io::Error {
+    io::Error::other("gzip support was not compiled in")
+}
+
+#[cfg(feature = "gzip")]
+fn save_gzip(file: File, memory: &[i64], format: Format) -> io::Result<()> {
+    let mut encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+    save(&mut encoder, memory, format)?;
+    encoder.finish().map(|_| ())
+}
+
+#[cfg(not(feature = "gzip"))]
+fn save_gzip(_file: File, _memory: &[i64], _format: Format) -> io::Result<()> {
+    Err(gzip_unsupported())
+}
+
+pub fn save_file<P: AsRef<Path>>(path: P, memory: &[i64], format: Format) -> io::Result<()> {
+    let path = path.as_ref();
+    let gzip = path.extension().is_some_and(|ext| ext == "gz");
+    // Checked first so an existing file isn't truncated for nothing
+    if gzip && !cfg!(feature = "gzip") {
+        return Err(gzip_unsupported());
+    }
+
+    let file = File::create(path)?;
+
+    if gzip {
+        save_gzip(file, memory, format)
+    } else {
+        save(file, memory, format)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env;
+    use std::fs;
+
+    const MEMORY: &[i64] = &[1, 0, -1, 99, 1 << 40, i64::MAX, i64::MIN, 1234567];
+
+    // Path in the temporary directory, unique to this process
+    fn temp_path(name: &str) -> PathBuf {
+        env::temp_dir().join(format!("loader_test_{}_{}", std::process::id(), name))
+    }
+
+    #[test]
+    fn text_round_trip() {
+        assert_eq!(parse_text(&to_text(MEMORY)).unwrap(), MEMORY);
+
+        let mut saved = Vec::new();
+        save(&mut saved, MEMORY, Format::Text).unwrap();
+        assert_eq!(load(&saved[..]).unwrap(), MEMORY);
+    }
+
+    #[test]
+    fn text_separators_and_comments() {
+        assert_eq!(parse_text("1,2 3\n\t4,,5 # 6,7\n# 8\n9,").unwrap(), vec![1, 2, 3, 4, 5, 9]);
+    }
+
+    fn parse_error(text: &str) -> (usize, usize, String) {
+        match parse_text(text).unwrap_err().kind {
+            LoadErrorKind::Parse { line, column, token } => (line, column, token),
+            kind => panic!("unexpected error {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn text_errors_point_at_the_token() {
+        assert_eq!(parse_error("1,2\n3, «é» ,4"), (2, 4, String::from("«é»")));
+        // Columns count characters, the ideographic spaces take 3 bytes each
+        assert_eq!(parse_error("1\u{3000}\u{3000}x2,3"), (1, 4, String::from("x2")));
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let bytes = to_binary(MEMORY);
+        assert!(bytes.starts_with(BINARY_MAGIC));
+        assert_eq!(parse_binary(&bytes).unwrap(), MEMORY);
+        assert_eq!(load(&bytes[..]).unwrap(), MEMORY);
+    }
+
+    #[test]
+    fn binary_errors() {
+        let bytes = to_binary(MEMORY);
+        assert!(matches!(parse_binary(&bytes[..bytes.len() - 1]).unwrap_err().kind, LoadErrorKind::Binary(_)));
+        assert!(matches!(parse_binary(&[&bytes[..], &[0]].concat()).unwrap_err().kind, LoadErrorKind::Binary(_)));
+        assert!(matches!(parse_binary(b"ICB").unwrap_err().kind, LoadErrorKind::Binary(_)));
+    }
+
+    #[test]
+    fn file_round_trip() {
+        for &(name, format) in &[("text.int", Format::Text), ("binary.icb", Format::Binary)] {
+            let path = temp_path(name);
+            save_file(&path, MEMORY, format).unwrap();
+            let loaded = load_file(&path);
+            fs::remove_file(&path).unwrap();
+            assert_eq!(loaded.unwrap(), MEMORY);
+        }
+    }
+
+    #[cfg(feature = "gzip")]
+    #[test]
+    fn gzip_round_trip() {
+        for &(name, format) in &[("text.int.gz", Format::Text), ("binary.icb.gz", Format::Binary)] {
+            let path = temp_path(name);
+            save_file(&path, MEMORY, format).unwrap();
+            let bytes = fs::read(&path).unwrap();
+            fs::remove_file(&path).unwrap();
+            assert!(bytes.starts_with(GZIP_MAGIC));
+            assert_eq!(parse_bytes(&bytes).unwrap(), MEMORY);
+        }
+    }
+
+    #[cfg(not(feature = "gzip"))]
+    #[test]
+    fn gzip_is_rejected_without_creating_the_file() {
+        let path = temp_path("rejected.int.gz");
+        assert!(save_file(&path, MEMORY, Format::Text).is_err());
+        assert!(!path.exists());
+        assert!(matches!(parse_bytes(GZIP_MAGIC).unwrap_err().kind, LoadErrorKind::GzipUnsupported));
+    }
+}
@@ -0,0 +1,12 @@
+pub mod cfg;
+pub mod compiler;
+pub mod dump;
+pub mod golden;
+pub mod loader;
+pub mod opcode;
+pub mod optimise;
+pub mod parallel;
+pub mod profile;
+pub mod session;
+pub mod threaded;
+pub mod vm;
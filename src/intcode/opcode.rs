@@ -0,0 +1,358 @@
+// Opcodes as pluggable implementations of the Opcode trait.
+//
+// Every Program owns a registry mapping opcode numbers (the two lowest digits of an instruction)
+// to implementations. The ten puzzle opcodes are registered by default and go through exactly the
+// same path as custom ones, which can be added to (or replace opcodes of) a single VM instance.
+
+use crate::intcode::vm::{ParamMode, Program, VmError};
+use std::fmt;
+use std::sync::Arc;
+
+pub const MAX_OPCODES: usize = 100;
+
+// What the VM does after an opcode executed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Flow {
+    Next,
+    Jump(i64),
+    NeedInput,
+    Halt,
+}
+
+pub trait Opcode: Send + Sync {
+    fn code(&self) -> i64;
+
+    fn name(&self) -> &str;
+
+    fn num_params(&self) -> usize;
+
+    // Parameters (starting at 1) that are written to, those can't use immediate mode
+    fn write_params(&self) -> &[usize] {
+        &[]
+    }
+
+    fn accepts_mode(&self, param_index: usize, mode: ParamMode) -> bool {
+        mode != ParamMode::Immediate || !self.write_params().contains(&param_index)
+    }
+
+    // Parameters are accessed through Program::get_param and Program::set_param
+    fn execute(&self, vm: &mut Program) -> Result<Flow, VmError>;
+}
+
+#[derive(Clone)]
+pub struct Registry {
+    opcodes: Vec<Option<Arc<dyn Opcode>>>,
+}
+
+impl Registry {
+    pub fn empty() -> Registry {
+        Registry { opcodes: vec![None; MAX_OPCODES] }
+    }
+
+    pub fn builtin() -> Registry {
+        let mut registry = Registry::empty();
+        registry.register(Arc::new(Add));
+        registry.register(Arc::new(Mul));
+        registry.register(Arc::new(Input));
+        registry.register(Arc::new(Output));
+        registry.register(Arc::new(JumpIfTrue));
+        registry.register(Arc::new(JumpIfFalse));
+        registry.register(Arc::new(LessThan));
+        registry.register(Arc::new(Equal));
+        registry.register(Arc::new(AdjustRelativeBase));
+        registry.register(Arc::new(Halt));
+        registry
+    }
+
+    // Returns the opcode previously registered with the same number
+    pub fn register(&mut self, opcode: Arc<dyn Opcode>) -> Option<Arc<dyn Opcode>> {
+        let code = opcode.code();
+        assert!((0..MAX_OPCODES as i64).contains(&code), "Opcode number {} out of range", code);
+        self.opcodes[code as usize].replace(opcode)
+    }
+
+    pub fn unregister(&mut self, code: i64) -> Option<Arc<dyn Opcode>> {
+        self.opcodes.get_mut(code as usize).and_then(|o| o.take())
+    }
+
+    pub fn get(&self, code: i64) -> Option<&Arc<dyn Opcode>> {
+        if code < 0 {
+            return None;
+        }
+        self.opcodes.get(code as usize).and_then(|o| o.as_ref())
+    }
+}
+
+impl fmt::Debug for Registry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list().entries(self.opcodes.iter().flatten().map(|o| (o.code(), o.name().to_string()))).finish()
+    }
+}
+
+struct Add;
+impl Opcode for Add {
+    fn code(&self) -> i64 {
+        1
+    }
+    fn name(&self) -> &str {
+        "add"
+    }
+    fn num_params(&self) -> usize {
+        3
+    }
+    fn write_params(&self) -> &[usize] {
+        &[3]
+    }
+    fn execute(&self, vm: &mut Program) -> Result<Flow, VmError> {
+        let sum = vm.get_param(1)? + vm.get_param(2)?;
+        vm.set_param(3, sum)?;
+        Ok(Flow::Next)
+    }
+}
+
+struct Mul;
+impl Opcode for Mul {
+    fn code(&self) -> i64 {
+        2
+    }
+    fn name(&self) -> &str {
+        "mul"
+    }
+    fn num_params(&self) -> usize {
+        3
+    }
+    fn write_params(&self) -> &[usize] {
+        &[3]
+    }
+    fn execute(&self, vm: &mut Program) -> Result<Flow, VmError> {
+        let product = vm.get_param(1)? * vm.get_param(2)?;
+        vm.set_param(3, product)?;
+        Ok(Flow::Next)
+    }
+}
+
+struct Input;
+impl Opcode for Input {
+    fn code(&self) -> i64 {
+        3
+    }
+    fn name(&self) -> &str {
+        "in"
+    }
+    fn num_params(&self) -> usize {
+        1
+    }
+    fn write_params(&self) -> &[usize] {
+        &[1]
+    }
+    fn execute(&self, vm: &mut Program) -> Result<Flow, VmError> {
+        match vm.pop_input() {
+            Some(input) => {
+                vm.set_param(1, input)?;
+                Ok(Flow::Next)
+            }
+            None => Ok(Flow::NeedInput),
+        }
+    }
+}
+
+struct Output;
+impl Opcode for Output {
+    fn code(&self) -> i64 {
+        4
+    }
+    fn name(&self) -> &str {
+        "out"
+    }
+    fn num_params(&self) -> usize {
+        1
+    }
+    fn execute(&self, vm: &mut Program) -> Result<Flow, VmError> {
+        let value = vm.get_param(1)?;
+        vm.push_output(value);
+        Ok(Flow::Next)
+    }
+}
+
+struct JumpIfTrue;
+impl Opcode for JumpIfTrue {
+    fn code(&self) -> i64 {
+        5
+    }
+    fn name(&self) -> &str {
+        "jnz"
+    }
+    fn num_params(&self) -> usize {
+        2
+    }
+    fn execute(&self, vm: &mut Program) -> Result<Flow, VmError> {
+        if vm.get_param(1)? != 0 {
+            Ok(Flow::Jump(vm.get_param(2)?))
+        } else {
+            Ok(Flow::Next)
+        }
+    }
+}
+
+struct JumpIfFalse;
+impl Opcode for JumpIfFalse {
+    fn code(&self) -> i64 {
+        6
+    }
+    fn name(&self) -> &str {
+        "jz"
+    }
+    fn num_params(&self) -> usize {
+        2
+    }
+    fn execute(&self, vm: &mut Program) -> Result<Flow, VmError> {
+        if vm.get_param(1)? == 0 {
+            Ok(Flow::Jump(vm.get_param(2)?))
+        } else {
+            Ok(Flow::Next)
+        }
+    }
+}
+
+struct LessThan;
+impl Opcode for LessThan {
+    fn code(&self) -> i64 {
+        7
+    }
+    fn name(&self) -> &str {
+        "lt"
+    }
+    fn num_params(&self) -> usize {
+        3
+    }
+    fn write_params(&self) -> &[usize] {
+        &[3]
+    }
+    fn execute(&self, vm: &mut Program) -> Result<Flow, VmError> {
+        let to_store = if vm.get_param(1)? < vm.get_param(2)? { 1 } else { 0 };
+        vm.set_param(3, to_store)?;
+        Ok(Flow::Next)
+    }
+}
+
+struct Equal;
+impl Opcode for Equal {
+    fn code(&self) -> i64 {
+        8
+    }
+    fn name(&self) -> &str {
+        "eq"
+    }
+    fn num_params(&self) -> usize {
+        3
+    }
+    fn write_params(&self) -> &[usize] {
+        &[3]
+    }
+    fn execute(&self, vm: &mut Program) -> Result<Flow, VmError> {
+        let to_store = if vm.get_param(1)? == vm.get_param(2)? { 1 } else { 0 };
+        vm.set_param(3, to_store)?;
+        Ok(Flow::Next)
+    }
+}
+
+struct AdjustRelativeBase;
+impl Opcode for AdjustRelativeBase {
+    fn code(&self) -> i64 {
+        9
+    }
+    fn name(&self) -> &str {
+        "arb"
+    }
+    fn num_params(&self) -> usize {
+        1
+    }
+    fn execute(&self, vm: &mut Program) -> Result<Flow, VmError> {
+        let offset = vm.get_param(1)?;
+        vm.adjust_relative_base(offset);
+        Ok(Flow::Next)
+    }
+}
+
+struct Halt;
+impl Opcode for Halt {
+    fn code(&self) -> i64 {
+        99
+    }
+    fn name(&self) -> &str {
+        "halt"
+    }
+    fn num_params(&self) -> usize {
+        0
+    }
+    fn execute(&self, _vm: &mut Program) -> Result<Flow, VmError> {
+        Ok(Flow::Halt)
+    }
+}
+
+// Prints its parameter to stderr, handy to trace values without disturbing the program's output
+pub struct DebugPrint {
+    pub code: i64,
+}
+
+impl Opcode for DebugPrint {
+    fn code(&self) -> i64 {
+        self.code
+    }
+    fn name(&self) -> &str {
+        "dbg"
+    }
+    fn num_params(&self) -> usize {
+        1
+    }
+    fn execute(&self, vm: &mut Program) -> Result<Flow, VmError> {
+        let value = vm.get_param(1)?;
+        eprintln!("[{}] {}", vm.ip(), value);
+        Ok(Flow::Next)
+    }
+}
+
+// Calls back into the host with the values of its input parameters, the result is stored in
+// the last parameter. Covers things like random numbers or access to host state.
+pub struct HostCall<F: Fn(&[i64]) -> i64 + Send + Sync> {
+    code: i64,
+    name: String,
+    num_inputs: usize,
+    write_param: [usize; 1],
+    function: F,
+}
+
+impl<F: Fn(&[i64]) -> i64 + Send + Sync> HostCall<F> {
+    pub fn new(code: i64, name: &str, num_inputs: usize, function: F) -> HostCall<F> {
+        HostCall {
+            code,
+            name: name.to_string(),
+            num_inputs,
+            write_param: [num_inputs + 1],
+            function,
+        }
+    }
+}
+
+impl<F: Fn(&[i64]) -> i64 + Send + Sync> Opcode for HostCall<F> {
+    fn code(&self) -> i64 {
+        self.code
+    }
+    fn name(&self) -> &str {
+        &self.name
+    }
+    fn num_params(&self) -> usize {
+        self.num_inputs + 1
+    }
+    fn write_params(&self) -> &[usize] {
+        &self.write_param
+    }
+    fn execute(&self, vm: &mut Program) -> Result<Flow, VmError> {
+        let mut args = Vec::with_capacity(self.num_inputs);
+        for i in 1..=self.num_inputs {
+            args.push(vm.get_param(i)?);
+        }
+        vm.set_param(self.num_inputs + 1, (self.function)(&args))?;
+        Ok(Flow::Next)
+    }
+}
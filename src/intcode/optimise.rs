@@ -0,0 +1,561 @@
+// Optimiser producing a semantically equivalent Intcode image that runs in fewer steps.
+//
+// Instructions keep their address, code is never moved as Intcode programs freely use absolute
+// addresses, so every rewrite replaces an instruction in place:
+// - constants stored in memory are propagated to the reads following them in straight-line code, then
+//   arithmetic and comparisons on two immediates are folded into a constant store
+// - conditional jumps on an immediate condition become unconditional jumps or never jump
+// - stores overwritten later in straight-line code without being read become jumps over themselves
+// - runs of instructions doing nothing (jumps never taken or to the next instruction, dead stores,
+//   adding 0 or multiplying by 1 in place) are skipped, the first one jumping past the run
+// - jumps to a run doing nothing or to an unconditional jump are threaded to the final target
+// Instructions that the program may overwrite (position mode writes into them) are left untouched.
+// Constants are forgotten at every address reached other than by falling through: jump targets and
+// immediates equal to an instruction address, which may be return addresses used by indirect jumps.
+// Relative mode writes are assumed to target the stack and not the code, `verify` checks the result
+// against the original program on sample inputs and counts the steps saved.
+
+use crate::intcode::cfg::{Cfg, Instruction, Operation, Param};
+use crate::intcode::vm::{ParamMode, Program, RunState, VmError};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt;
+
+#[derive(Default)]
+pub struct Report {
+    pub propagated: usize,
+    pub folded: usize,
+    pub constant_jumps: usize,
+    pub dead_stores: usize,
+    pub skipped: usize,
+    pub threaded: usize,
+    pub indirect_jumps: usize,
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} constants propagated, {} folded, {} constant jumps, {} dead stores, {} instructions skipped, {} threaded jumps ({} indirect jumps not followed)",
+            self.propagated, self.folded, self.constant_jumps, self.dead_stores, self.skipped, self.threaded, self.indirect_jumps
+        )
+    }
+}
+
+fn immediate(value: i64) -> Param {
+    Param { mode: ParamMode::Immediate, value }
+}
+
+fn replace(image: &mut [i64], inst: &Instruction) {
+    for (i, word) in inst.encode().into_iter().enumerate() {
+        image[inst.address + i] = word;
+    }
+}
+
+// Unconditional jump from `inst` to `target`, same length or shorter than any instruction doing nothing
+fn jump(inst: &Instruction, target: usize) -> Instruction {
+    Instruction {
+        address: inst.address,
+        opcode: Operation::JumpIfTrue,
+        params: vec![immediate(1), immediate(target as i64)],
+    }
+}
+
+// Instruction now in the image at the address of `inst`, None when the program may overwrite it
+fn current(image: &[i64], cfg: &Cfg, inst: &Instruction) -> Option<Instruction> {
+    if cfg.is_self_modified(inst) {
+        return None;
+    }
+    Instruction::decode(image, inst.address)
+}
+
+// Addresses execution may reach other than by falling through
+fn entry_points(cfg: &Cfg) -> BTreeSet<usize> {
+    let mut entries = cfg.block_starts.clone();
+    for inst in cfg.instructions.values() {
+        for value in inst.params.iter().filter_map(|p| p.immediate()) {
+            if value >= 0 && cfg.instructions.contains_key(&(value as usize)) {
+                entries.insert(value as usize);
+            }
+        }
+    }
+    entries
+}
+
+// Value stored by an arithmetic or comparison on two immediates
+fn constant_result(inst: &Instruction) -> Option<i64> {
+    let (a, b) = (inst.params.first()?.immediate()?, inst.params.get(1)?.immediate()?);
+    match inst.opcode {
+        Operation::Add => a.checked_add(b),
+        Operation::Mul => a.checked_mul(b),
+        Operation::LessThan => Some(if a < b { 1 } else { 0 }),
+        Operation::Equal => Some(if a == b { 1 } else { 0 }),
+        _ => None,
+    }
+}
+
+fn fold(inst: &mut Instruction, report: &mut Report) {
+    if inst.is_jump() {
+        if inst.constant_condition() == Some(true) {
+            inst.opcode = Operation::JumpIfTrue;
+            inst.params[0] = immediate(1);
+        }
+        return;
+    }
+
+    // Already in the folded form
+    if inst.opcode == Operation::Add && inst.params[1].immediate() == Some(0) && inst.params[0].immediate().is_some() {
+        return;
+    }
+
+    if let Some(result) = constant_result(inst) {
+        *inst = Instruction {
+            address: inst.address,
+            opcode: Operation::Add,
+            params: vec![immediate(result), immediate(0), inst.params[2]],
+        };
+        report.folded += 1;
+    }
+}
+
+fn propagate_constants(image: &mut [i64], cfg: &Cfg, entries: &BTreeSet<usize>, report: &mut Report) {
+    // Constant values of memory addresses at the current point of the straight-line code
+    let mut known: HashMap<usize, i64> = HashMap::new();
+    let mut fallthrough = None;
+
+    for inst in cfg.instructions.values() {
+        if fallthrough != Some(inst.address) || entries.contains(&inst.address) {
+            known.clear();
+        }
+        fallthrough = Some(inst.next());
+
+        let original = match current(image, cfg, inst) {
+            Some(original) => original,
+            None => {
+                known.clear();
+                continue;
+            }
+        };
+
+        let mut rewritten = original.clone();
+        let write_param = rewritten.opcode.write_param();
+        for (i, param) in rewritten.params.iter_mut().enumerate() {
+            if let Some(&value) = param.position().and_then(|address| known.get(&address)).filter(|_| write_param != Some(i)) {
+                *param = immediate(value);
+                report.propagated += 1;
+            }
+        }
+        fold(&mut rewritten, report);
+
+        if rewritten != original {
+            if rewritten.is_jump() && rewritten.constant_condition().is_some() {
+                report.constant_jumps += 1;
+            }
+            replace(image, &rewritten);
+        }
+
+        if let Some(target) = rewritten.write_target() {
+            match target.position() {
+                Some(address) => match constant_result(&rewritten) {
+                    Some(value) => known.insert(address, value),
+                    None => known.remove(&address),
+                },
+                // Relative writes may land anywhere
+                None => {
+                    known.clear();
+                    None
+                }
+            };
+        }
+    }
+}
+
+// Whether `inst`, followed by the instruction at `next`, leaves everything as it was
+fn is_no_op(inst: &Instruction, next: usize) -> bool {
+    let in_place = |identity: i64| match (inst.params[0].immediate(), inst.params[1].immediate()) {
+        (Some(value), None) => value == identity && inst.params[1].position().is_some() && inst.params[1] == inst.params[2],
+        (None, Some(value)) => value == identity && inst.params[0].position().is_some() && inst.params[0] == inst.params[2],
+        _ => false,
+    };
+
+    match inst.opcode {
+        Operation::JumpIfTrue | Operation::JumpIfFalse => match inst.constant_condition() {
+            Some(false) => true,
+            Some(true) => inst.jump_target() == Some(next),
+            None => false,
+        },
+        Operation::Add => in_place(0),
+        Operation::Mul => in_place(1),
+        _ => false,
+    }
+}
+
+fn reads_position(inst: &Instruction, address: usize) -> bool {
+    inst.params.iter().enumerate().any(|(i, p)| inst.opcode.write_param() != Some(i) && p.position() == Some(address))
+}
+
+// Whether the value stored by `inst` is overwritten in the straight-line code following it before
+// anything may read it
+fn is_dead_store(image: &[i64], cfg: &Cfg, original: &Instruction, inst: &Instruction) -> bool {
+    // Input has a side effect even if the value is never used
+    if inst.opcode == Operation::Input || is_no_op(inst, original.next()) {
+        return false;
+    }
+    let address = match inst.write_target().and_then(|p| p.position()) {
+        Some(address) => address,
+        None => return false,
+    };
+    // Stores into code are self modification, and the operands may be read by the store itself
+    if cfg.instructions.range(..=address).next_back().is_some_and(|(_, i)| address < i.next()) || reads_position(inst, address) {
+        return false;
+    }
+
+    let mut next = original.next();
+    while let Some(later_original) = cfg.instructions.get(&next) {
+        let later = match current(image, cfg, later_original) {
+            Some(later) => later,
+            None => return false,
+        };
+        next = later_original.next();
+        if is_no_op(&later, next) {
+            continue;
+        }
+
+        let uses_relative = later.params.iter().any(|p| p.mode == ParamMode::Relative);
+        if reads_position(&later, address) || uses_relative || later.is_jump() || later.opcode == Operation::Halt {
+            return false;
+        }
+        if later.write_target().and_then(|p| p.position()) == Some(address) {
+            return true;
+        }
+    }
+
+    false
+}
+
+fn remove_dead_stores(image: &mut [i64], cfg: &Cfg, report: &mut Report) {
+    for original in cfg.instructions.values() {
+        let dead = current(image, cfg, original).is_some_and(|inst| is_dead_store(image, cfg, original, &inst));
+        if dead {
+            replace(image, &jump(original, original.next()));
+            report.dead_stores += 1;
+        }
+    }
+}
+
+// Makes the first instruction of every run doing nothing jump past the run, returns where execution
+// continues from each instruction of the runs
+fn skip_no_ops(image: &mut [i64], cfg: &Cfg, report: &mut Report) -> BTreeMap<usize, usize> {
+    let mut run_end = BTreeMap::new();
+    for original in cfg.instructions.values().rev() {
+        if current(image, cfg, original).is_some_and(|inst| is_no_op(&inst, original.next())) {
+            let end = run_end.get(&original.next()).copied().unwrap_or_else(|| original.next());
+            run_end.insert(original.address, end);
+        }
+    }
+
+    for (&address, &end) in &run_end {
+        let inst = &cfg.instructions[&address];
+        let first = !cfg
+            .instructions
+            .range(..address)
+            .next_back()
+            .is_some_and(|(_, before)| before.next() == address && run_end.contains_key(&before.address));
+        let length = cfg.instructions.range(address..end).count();
+        if first && length > 1 {
+            replace(image, &jump(inst, end));
+            report.skipped += length - 1;
+        }
+    }
+
+    run_end
+}
+
+fn thread_jumps(image: &mut [i64], cfg: &Cfg, run_end: &BTreeMap<usize, usize>, report: &mut Report) {
+    // Where execution really continues after reaching `address`, if it is somewhere else
+    let shortcut = |image: &[i64], address: usize| -> Option<usize> {
+        if let Some(&end) = run_end.get(&address) {
+            return Some(end);
+        }
+        let inst = current(image, cfg, cfg.instructions.get(&address)?)?;
+        if inst.constant_condition() != Some(true) {
+            return None;
+        }
+        inst.jump_target()
+    };
+
+    for original in cfg.instructions.values() {
+        let inst = match current(image, cfg, original) {
+            Some(inst) if inst.is_jump() && inst.constant_condition() != Some(false) => inst,
+            _ => continue,
+        };
+        let first_target = match inst.jump_target() {
+            Some(target) => target,
+            None => continue,
+        };
+
+        let mut target = first_target;
+        let mut hops = 0;
+        while let Some(next) = shortcut(image, target) {
+            hops += 1;
+            // Guards against jump cycles
+            if next == target || hops > cfg.instructions.len() {
+                break;
+            }
+            target = next;
+        }
+
+        if target != first_target {
+            let mut threaded = inst.clone();
+            threaded.params[1] = immediate(target as i64);
+            replace(image, &threaded);
+            report.threaded += 1;
+        }
+    }
+}
+
+pub fn optimise(image: &[i64]) -> (Vec<i64>, Report) {
+    let cfg = Cfg::build(image);
+    let mut optimised = image.to_vec();
+    let mut report = Report {
+        indirect_jumps: cfg.indirect_jumps.len(),
+        ..Report::default()
+    };
+
+    propagate_constants(&mut optimised, &cfg, &entry_points(&cfg), &mut report);
+    remove_dead_stores(&mut optimised, &cfg, &mut report);
+    let run_end = skip_no_ops(&mut optimised, &cfg, &mut report);
+    thread_jumps(&mut optimised, &cfg, &run_end, &mut report);
+
+    (optimised, report)
+}
+
+pub struct Comparison {
+    pub original_steps: u64,
+    pub optimised_steps: u64,
+}
+
+impl Comparison {
+    // Steps the optimised program saves, negative if it is slower
+    pub fn saved(&self) -> i64 {
+        self.original_steps as i64 - self.optimised_steps as i64
+    }
+}
+
+fn run_sample(image: &[i64], input: &[i64]) -> Result<(RunState, Vec<i64>, u64), VmError> {
+    let mut program = Program::new(image);
+    let state = program.run_with_input(input)?;
+    Ok((state, program.take_output(), program.steps()))
+}
+
+// Runs both programs side by side on every sample input and checks they behave identically
+pub fn verify(original: &[i64], optimised: &[i64], samples: &[Vec<i64>]) -> Result<Vec<Comparison>, String> {
+    let mut comparisons = Vec::new();
+
+    for (i, input) in samples.iter().enumerate() {
+        let expected = run_sample(original, input);
+        let actual = run_sample(optimised, input);
+
+        match (expected, actual) {
+            (Ok((expected_state, expected_output, original_steps)), Ok((actual_state, actual_output, optimised_steps))) => {
+                if expected_state != actual_state || expected_output != actual_output {
+                    return Err(format!(
+                        "sample {}: original {} with {:?}, optimised {} with {:?}",
+                        i, expected_state, expected_output, actual_state, actual_output
+                    ));
+                }
+                comparisons.push(Comparison { original_steps, optimised_steps });
+            }
+            (Err(e), Err(_)) => return Err(format!("sample {}: original program failed: {}", i, e)),
+            (Ok(_), Err(e)) => return Err(format!("sample {}: optimised program failed: {}", i, e)),
+            (Err(e), Ok(_)) => return Err(format!("sample {}: original program failed but not the optimised one: {}", i, e)),
+        }
+    }
+
+    Ok(comparisons)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intcode::compiler::codegen::compile;
+    use crate::intcode::loader;
+
+    // Image padded with zeros up to `len` values, room for the data
+    fn padded(code: &[i64], len: usize) -> Vec<i64> {
+        let mut image = code.to_vec();
+        image.resize(len, 0);
+        image
+    }
+
+    // Optimises the image and checks it against the original, returns the steps before and after
+    fn steps(image: &[i64], input: &[i64]) -> (u64, u64) {
+        let (optimised, _) = optimise(image);
+        let comparisons = verify(image, &optimised, &[input.to_vec()]).unwrap_or_else(|e| panic!("{}", e));
+        (comparisons[0].original_steps, comparisons[0].optimised_steps)
+    }
+
+    #[test]
+    fn constants_and_dead_stores_are_skipped() {
+        let image = padded(
+            &[
+                1102, 3, 5, 30, // [30] = 3 * 5, dead once propagated
+                1007, 30, 10, 30, // [30] = [30] < 10, constant 0 and dead
+                1005, 30, 24, // never taken
+                1101, 1, 1, 31, // [31] = 2
+                4, 31, // output 2
+                1101, 5, 5, 30, // overwrites [30]
+                99, 0, 0, //
+                104, -1, 99,
+            ],
+            32,
+        );
+        let (optimised, report) = optimise(&image);
+        assert_eq!((report.propagated, report.folded, report.constant_jumps, report.dead_stores, report.skipped), (3, 4, 1, 2, 2));
+        // The first instruction jumps over the 2 others
+        assert_eq!(&optimised[0..3], &[1105, 1, 11]);
+        assert_eq!(steps(&image, &[]), (7, 5));
+    }
+
+    #[test]
+    fn jump_chains_are_threaded() {
+        let image = padded(
+            &[
+                1001, 40, 1, 40, // [40] += 1
+                1007, 40, 5, 41, // [41] = [40] < 5
+                1005, 41, 14, // loop through 3 jumps
+                4, 40, 99, //
+                1105, 1, 17, //
+                1105, 1, 20, //
+                1105, 1, 0,
+            ],
+            42,
+        );
+        let (optimised, report) = optimise(&image);
+        assert_eq!(report.threaded, 3);
+        assert_eq!(optimised[10], 0);
+        assert_eq!(steps(&image, &[]), (29, 17));
+    }
+
+    #[test]
+    fn runs_doing_nothing_are_skipped() {
+        let image = padded(
+            &[
+                1001, 50, 1, 50, // [50] += 1
+                1106, 1, 0, // never taken
+                1001, 50, 0, 50, // [50] += 0
+                1002, 50, 1, 50, // [50] *= 1
+                1007, 50, 3, 51, // [51] = [50] < 3
+                1005, 51, 0, //
+                4, 50, 99,
+            ],
+            52,
+        );
+        let (optimised, report) = optimise(&image);
+        assert_eq!(report.skipped, 2);
+        assert_eq!(&optimised[4..7], &[1105, 1, 15]);
+        assert_eq!(steps(&image, &[]), (20, 14));
+    }
+
+    #[test]
+    fn self_modified_code_is_left_alone() {
+        // The first instruction clears the condition of the jump, which then falls through
+        let image = vec![1101, 0, 0, 5, 1105, 1, 10, 104, 7, 99, 104, 10, 99];
+        let (optimised, _) = optimise(&image);
+        assert_eq!(optimised, image);
+        assert_eq!(steps(&image, &[]), (4, 4));
+    }
+
+    #[test]
+    fn input_stores_are_kept() {
+        // Both inputs are read even though the first value is overwritten straight away
+        let image = padded(&[3, 20, 3, 20, 4, 20, 99], 21);
+        let (optimised, _) = optimise(&image);
+        assert_eq!(optimised, image);
+        assert_eq!(steps(&image, &[1, 2]), (4, 4));
+    }
+
+    #[test]
+    fn day_programs_keep_their_behaviour() {
+        for &(path, inputs) in &[("input_day5.txt", &[1, 5][..]), ("input_day9.txt", &[1][..]), ("input_day17.txt", &[][..])] {
+            let image = loader::load_file(path).unwrap();
+            let (optimised, _) = optimise(&image);
+            let samples: Vec<Vec<i64>> = if inputs.is_empty() { vec![Vec::new()] } else { inputs.iter().map(|&i| vec![i]).collect() };
+            for comparison in verify(&image, &optimised, &samples).unwrap_or_else(|e| panic!("{}: {}", path, e)) {
+                assert!(comparison.saved() >= 0, "{} is slower", path);
+            }
+        }
+    }
+
+    #[test]
+    fn code_after_a_return_is_analysed() {
+        // The return site of the call clears the condition of the first jump, which must not be skipped
+        let image = padded(
+            &[
+                1106, 1, 20, // never taken at first
+                1106, 1, 20, //
+                1101, 13, 0, 40, // [40] = 13, the return address
+                1105, 1, 23, // call
+                1101, 0, 0, 1, // [1] = 0
+                1105, 1, 0, // back to the start, now jumping to 20
+                104, 2, 99, //
+                104, 1, 106, 0, 40, // output 1 and return through [40]
+            ],
+            41,
+        );
+        let cfg = Cfg::build(&image);
+        assert!(cfg.instructions.contains_key(&13) && cfg.static_writes.contains(&1));
+        let (optimised, _) = optimise(&image);
+        assert_eq!(&optimised[0..3], &image[0..3]);
+        assert_eq!(verify(&image, &optimised, &[Vec::new()]).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn optimised_program_is_equivalent() {
+        let source = "
+            fn square(x) {
+                return x * x;
+            }
+
+            fn main() {
+                let n = input();
+                let i = 1 + 0 * 5;
+                while i <= n {
+                    output(square(i) + 2 * 3);
+                    i = i + 1;
+                }
+            }
+        ";
+        let image = compile(source).unwrap();
+        let (optimised, _) = optimise(&image);
+        let samples = vec![vec![0], vec![1], vec![6]];
+        assert!(verify(&image, &optimised, &samples).is_ok());
+    }
+
+    #[test]
+    fn optimised_nested_ifs_run_in_fewer_steps() {
+        // The end of every inner branch jumps to the end of the outer one, threading skips the middle jump
+        let source = "
+            fn main() {
+                let n = input();
+                while n > 0 {
+                    if n > 2 {
+                        if n > 4 {
+                            output(3);
+                        } else {
+                            output(2);
+                        }
+                    } else {
+                        output(1);
+                    }
+                    n = n - 1;
+                }
+            }
+        ";
+        let image = compile(source).unwrap();
+        let (optimised, report) = optimise(&image);
+        assert!(report.threaded > 0);
+        let comparisons = verify(&image, &optimised, &[vec![0], vec![3], vec![6]]).unwrap();
+        assert!(comparisons.iter().all(|c| c.saved() >= 0));
+        assert!(comparisons[2].saved() > 0);
+    }
+}
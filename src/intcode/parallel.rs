@@ -0,0 +1,150 @@
+// Parallel search over many independent executions of the same base program.
+//
+// Every candidate gets its own clone of the base (an image or a Program) and the results are
+// always combined in candidate order, so the answer doesn't depend on the number of threads or
+// on which thread finished first.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+
+pub fn default_threads() -> usize {
+    thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+}
+
+// Hands out candidate indices to the workers until `limit` (which may shrink while running) is reached
+fn run_workers<W: Fn(usize) + Sync>(num_candidates: usize, threads: usize, limit: &AtomicUsize, work: W) {
+    let next = AtomicUsize::new(0);
+
+    thread::scope(|s| {
+        for _ in 0..threads.clamp(1, num_candidates.max(1)) {
+            s.spawn(|| loop {
+                let index = next.fetch_add(1, Ordering::Relaxed);
+                if index >= num_candidates || index >= limit.load(Ordering::Relaxed) {
+                    break;
+                }
+                work(index);
+            });
+        }
+    });
+}
+
+// Runs `f` on every candidate, results are in candidate order
+pub fn map<B, C, R, F>(base: &B, candidates: &[C], threads: usize, f: F) -> Vec<R>
+where
+    B: Clone + Sync,
+    C: Sync,
+    R: Send,
+    F: Fn(B, &C) -> R + Sync,
+{
+    let results: Mutex<Vec<Option<R>>> = Mutex::new((0..candidates.len()).map(|_| None).collect());
+    let limit = AtomicUsize::new(candidates.len());
+
+    run_workers(candidates.len(), threads, &limit, |index| {
+        let result = f(base.clone(), &candidates[index]);
+        results.lock().unwrap()[index] = Some(result);
+    });
+
+    results.into_inner().unwrap().into_iter().map(|r| r.unwrap()).collect()
+}
+
+// Returns the first candidate (in candidate order) for which `f` returns Some, with its index.
+// Candidates after an already found match are skipped.
+pub fn find_first<B, C, R, F>(base: &B, candidates: &[C], threads: usize, f: F) -> Option<(usize, R)>
+where
+    B: Clone + Sync,
+    C: Sync,
+    R: Send,
+    F: Fn(B, &C) -> Option<R> + Sync,
+{
+    let found: Mutex<Option<(usize, R)>> = Mutex::new(None);
+    let limit = AtomicUsize::new(candidates.len());
+
+    run_workers(candidates.len(), threads, &limit, |index| {
+        if let Some(result) = f(base.clone(), &candidates[index]) {
+            limit.fetch_min(index, Ordering::Relaxed);
+            let mut found = found.lock().unwrap();
+            if found.as_ref().is_none_or(|(found_index, _)| index < *found_index) {
+                *found = Some((index, result));
+            }
+        }
+    });
+
+    found.into_inner().unwrap()
+}
+
+// Returns the candidate with the greatest result, ties going to the earliest candidate
+pub fn find_best<B, C, R, F>(base: &B, candidates: &[C], threads: usize, f: F) -> Option<(usize, R)>
+where
+    B: Clone + Sync,
+    C: Sync,
+    R: Ord + Send,
+    F: Fn(B, &C) -> R + Sync,
+{
+    let mut best: Option<(usize, R)> = None;
+
+    for (index, result) in map(base, candidates, threads, f).into_iter().enumerate() {
+        if best.as_ref().is_none_or(|(_, best_result)| result > *best_result) {
+            best = Some((index, result));
+        }
+    }
+
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    const THREADS: [usize; 3] = [1, 4, 16];
+
+    #[test]
+    fn map_keeps_candidate_order() {
+        let candidates: Vec<u64> = (0..40).collect();
+        for &threads in &THREADS {
+            // The first candidates take the longest, so they finish last
+            let results = map(&10, &candidates, threads, |base, &c| {
+                thread::sleep(Duration::from_millis((40 - c) / 8));
+                base + c
+            });
+            assert_eq!(results, (10..50).collect::<Vec<u64>>(), "{} threads", threads);
+        }
+        assert!(map(&0, &[] as &[u64], 4, |base, &c| base + c).is_empty());
+    }
+
+    #[test]
+    fn find_first_returns_the_lowest_index() {
+        let candidates: Vec<u64> = (0..100).collect();
+        for &threads in &THREADS {
+            let found = find_first(&vec![7], &candidates, threads, |base, &c| if c > 0 && c % base[0] == 0 { Some(c * 2) } else { None });
+            assert_eq!(found, Some((7, 14)), "{} threads", threads);
+        }
+        assert_eq!(find_first(&0, &candidates, 4, |_, _| None::<u64>), None);
+    }
+
+    #[test]
+    fn find_first_waits_for_a_slower_earlier_match() {
+        let candidates: Vec<u64> = (0..20).collect();
+        for &threads in &THREADS {
+            let found = find_first(&(), &candidates, threads, |_, &c| match c {
+                2 => {
+                    thread::sleep(Duration::from_millis(50));
+                    Some(c)
+                }
+                c if c >= 5 => Some(c),
+                _ => None,
+            });
+            assert_eq!(found, Some((2, 2)), "{} threads", threads);
+        }
+    }
+
+    #[test]
+    fn find_best_breaks_ties_on_the_earliest_candidate() {
+        let candidates = [3, 9, 2, 9, 1];
+        for &threads in &THREADS {
+            assert_eq!(find_best(&1, &candidates, threads, |base, &c| base * c), Some((1, 9)), "{} threads", threads);
+        }
+        assert_eq!(find_best(&1, &[] as &[i64], 4, |base, &c| base * c), None);
+    }
+}
@@ -0,0 +1,74 @@
+// Instruction set profiles matching each generation of the Intcode puzzles.
+//
+// Day 2 only has add, multiply and halt with position mode parameters, day 5 adds input, output,
+// jumps and comparisons with immediate mode, day 9 adds relative mode, the relative base opcode and
+// memory beyond the initial program.
+
+use crate::intcode::vm::ParamMode;
+use std::fmt;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Profile {
+    Day2,
+    Day5,
+    Day9,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Violation {
+    Opcode { address: usize, opcode: i64 },
+    Mode { address: usize, mode: ParamMode },
+    MemoryGrowth { address: usize, target: usize },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Violation::Opcode { address, opcode } => write!(f, "opcode {} at address {}", opcode, address),
+            Violation::Mode { address, mode } => write!(f, "{:?} mode parameter at address {}", mode, address),
+            Violation::MemoryGrowth { address, target } => write!(f, "access beyond the program at address {} by instruction at address {}", target, address),
+        }
+    }
+}
+
+impl Profile {
+    pub const ALL: [Profile; 3] = [Profile::Day2, Profile::Day5, Profile::Day9];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Profile::Day2 => "day2",
+            Profile::Day5 => "day5",
+            Profile::Day9 => "day9",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Profile> {
+        Profile::ALL.iter().cloned().find(|p| p.name() == name)
+    }
+
+    pub fn supports_opcode(self, opcode: i64) -> bool {
+        match self {
+            Profile::Day2 => opcode == 1 || opcode == 2 || opcode == 99,
+            Profile::Day5 => (1..=8).contains(&opcode) || opcode == 99,
+            Profile::Day9 => (1..=9).contains(&opcode) || opcode == 99,
+        }
+    }
+
+    pub fn supports_mode(self, mode: ParamMode) -> bool {
+        match self {
+            Profile::Day2 => mode == ParamMode::Position,
+            Profile::Day5 => mode != ParamMode::Relative,
+            Profile::Day9 => true,
+        }
+    }
+
+    pub fn growable_memory(self) -> bool {
+        self == Profile::Day9
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
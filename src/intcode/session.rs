@@ -0,0 +1,334 @@
+// Recording and replay of the I/O of a program.
+//
+// A session file lists every value a program consumed and produced, in order, one per line with the
+// number of instructions executed before the one consuming or producing it:
+//   12 in 5
+//   40 out 7
+//   41 halt
+// `#` starts a comment. A session without a final `halt` line was stopped while the program was
+// still running. Replaying feeds the recorded inputs back only when the program asks for them and
+// checks every output against the recording, stopping at the first difference.
+// The days feeding their program inputs computed on the fly record it through a `Sessions` sink given
+// by the runner, keeping the image the program started from with its session.
+
+use crate::intcode::vm::{Program, RunState, VmError};
+use std::fmt;
+use std::fs;
+
+// Instructions a replay may execute past the last recorded event before giving up
+const MAX_EXTRA_STEPS: u64 = 10_000_000;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Event {
+    Input(i64),
+    Output(i64),
+    Halt,
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Event::Input(value) => write!(f, "in {}", value),
+            Event::Output(value) => write!(f, "out {}", value),
+            Event::Halt => write!(f, "halt"),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Entry {
+    pub step: u64,
+    pub event: Event,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Session {
+    pub entries: Vec<Entry>,
+}
+
+impl Session {
+    pub fn push(&mut self, step: u64, event: Event) {
+        self.entries.push(Entry { step, event });
+    }
+
+    pub fn inputs(&self) -> Vec<i64> {
+        self.entries
+            .iter()
+            .filter_map(|e| match e.event {
+                Event::Input(value) => Some(value),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn outputs(&self) -> Vec<i64> {
+        self.entries
+            .iter()
+            .filter_map(|e| match e.event {
+                Event::Output(value) => Some(value),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub fn parse(text: &str) -> Result<Session, String> {
+        let mut session = Session::default();
+
+        for (line_index, line) in text.lines().enumerate() {
+            let line = match line.find('#') {
+                Some(pos) => &line[..pos],
+                None => line,
+            };
+            let words: Vec<&str> = line.split_whitespace().collect();
+            if words.is_empty() {
+                continue;
+            }
+
+            let err = || format!("line {}: invalid entry `{}`", line_index + 1, line.trim());
+            let step = words[0].parse().map_err(|_| err())?;
+            let event = match words[1..] {
+                ["in", value] => Event::Input(value.parse().map_err(|_| err())?),
+                ["out", value] => Event::Output(value.parse().map_err(|_| err())?),
+                ["halt"] => Event::Halt,
+                _ => return Err(err()),
+            };
+            session.push(step, event);
+        }
+
+        Ok(session)
+    }
+
+    pub fn to_text(&self) -> String {
+        self.entries.iter().map(|e| format!("{} {}\n", e.step, e.event)).collect()
+    }
+
+    pub fn load_file(path: &str) -> Result<Session, String> {
+        let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        Session::parse(&text).map_err(|e| format!("{}: {}", path, e))
+    }
+
+    pub fn save_file(&self, path: &str) -> Result<(), String> {
+        fs::write(path, self.to_text()).map_err(|e| format!("{}: {}", path, e))
+    }
+}
+
+// What the program did instead of the recorded event
+#[derive(Clone, Debug, PartialEq)]
+pub enum Actual {
+    Event(Event),
+    InputRequest,
+    Error(VmError),
+    StepLimit,
+}
+
+impl fmt::Display for Actual {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Actual::Event(event) => write!(f, "{}", event),
+            Actual::InputRequest => write!(f, "a request for input"),
+            Actual::Error(e) => write!(f, "error: {}", e),
+            Actual::StepLimit => write!(f, "no event after {} more steps", MAX_EXTRA_STEPS),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Divergence {
+    // Index of the first entry that didn't match
+    pub index: usize,
+    pub step: u64,
+    pub ip: usize,
+    pub expected: Option<Entry>,
+    pub actual: Actual,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "event {} at step {} (ip {}): expected ", self.index, self.step, self.ip)?;
+        match &self.expected {
+            Some(entry) => write!(f, "{} (recorded at step {})", entry.event, entry.step)?,
+            None => write!(f, "end of session")?,
+        }
+        write!(f, ", got {}", self.actual)
+    }
+}
+
+// Replays the session on the image, returns the number of events matched
+pub fn replay(image: &[i64], session: &Session) -> Result<usize, Divergence> {
+    let mut program = Program::new(image);
+    let entries = &session.entries;
+    let mut index = 0;
+    let mut max_steps = MAX_EXTRA_STEPS;
+
+    loop {
+        let expected = entries.get(index).cloned();
+        let (step, ip) = (program.steps(), program.ip());
+        let diverge = |actual: Actual| Divergence { index, step, ip, expected, actual };
+
+        // A session stopped before the program halted ends as soon as every event matched
+        if expected.is_none() && entries.last().is_none_or(|e| e.event != Event::Halt) {
+            return Ok(index);
+        }
+        if program.steps() >= max_steps {
+            return Err(diverge(Actual::StepLimit));
+        }
+
+        let state = match program.step() {
+            Ok(state) => state,
+            Err(e) => return Err(diverge(Actual::Error(e))),
+        };
+
+        let actual = match (program.pop_output(), state) {
+            (Some(value), _) => Event::Output(value),
+            (None, Some(RunState::Halted)) => Event::Halt,
+            (None, Some(RunState::NeedInput)) => match expected {
+                Some(Entry { event: Event::Input(value), .. }) => {
+                    program.push_input(value);
+                    Event::Input(value)
+                }
+                _ => return Err(diverge(Actual::InputRequest)),
+            },
+            (None, None) => continue,
+        };
+
+        if expected.map(|e| e.event) != Some(actual) {
+            return Err(diverge(Actual::Event(actual)));
+        }
+        index += 1;
+        max_steps = program.steps() + MAX_EXTRA_STEPS;
+
+        if actual == Event::Halt {
+            return Ok(index);
+        }
+    }
+}
+
+// Runs the program while recording its I/O, feeding every batch of inputs once it waits for input,
+// returns the session and the state the program stopped in
+pub fn record(image: &[i64], inputs: &[Vec<i64>]) -> Result<(Session, RunState), VmError> {
+    let mut program = Program::new(image);
+    program.start_recording();
+
+    let mut state = program.run()?;
+    for values in inputs {
+        state = program.run_with_input(values)?;
+    }
+    Ok((program.take_recording().unwrap(), state))
+}
+
+// Program run by a day, with the image it started from as the day may patch its input
+#[derive(Clone, Debug, PartialEq)]
+pub struct Recording {
+    pub image: Vec<i64>,
+    pub session: Session,
+}
+
+// Recordings of the programs a day runs, nothing is recorded in normal runs
+pub struct Sessions {
+    enabled: bool,
+    recordings: Vec<Recording>,
+}
+
+impl Sessions {
+    pub fn new() -> Sessions {
+        Sessions {
+            enabled: true,
+            recordings: Vec::new(),
+        }
+    }
+
+    pub fn disabled() -> Sessions {
+        Sessions { enabled: false, ..Sessions::new() }
+    }
+
+    // Program running the image, recording its I/O when the sessions are kept
+    pub fn program(&self, image: &[i64]) -> Program {
+        let mut program = Program::new(image);
+        if self.enabled {
+            program.start_recording();
+        }
+        program
+    }
+
+    // Keeps the session of a program started by `program` from the image, once the day is done with it
+    pub fn keep(&mut self, image: &[i64], program: &mut Program) {
+        if let Some(session) = program.take_recording() {
+            self.recordings.push(Recording { image: image.to_vec(), session });
+        }
+    }
+
+    pub fn take(&mut self) -> Vec<Recording> {
+        std::mem::take(&mut self.recordings)
+    }
+}
+
+impl Default for Sessions {
+    fn default() -> Sessions {
+        Sessions::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intcode::loader;
+
+    #[test]
+    fn recording_replays() {
+        let image = loader::load_file("input_day9.txt").unwrap();
+        let (session, state) = record(&image, &[vec![1]]).unwrap();
+        assert_eq!(state, RunState::Halted);
+        assert_eq!(session.inputs(), vec![1]);
+        assert_eq!(session.entries.last().map(|e| e.event), Some(Event::Halt));
+
+        let reloaded = Session::parse(&session.to_text()).unwrap();
+        assert_eq!(reloaded, session);
+        assert_eq!(replay(&image, &reloaded), Ok(session.entries.len()));
+    }
+
+    #[test]
+    fn stopped_recording_replays() {
+        // The robot keeps running, waiting for the colour under it
+        let image = loader::load_file("input_day11.txt").unwrap();
+        let (session, state) = record(&image, &[vec![0], vec![1], vec![1]]).unwrap();
+        assert_eq!(state, RunState::NeedInput);
+        assert_eq!(session.entries.first().map(|e| e.event), Some(Event::Input(0)));
+        assert_ne!(session.entries.last().map(|e| e.event), Some(Event::Halt));
+        assert_eq!(replay(&image, &session), Ok(session.entries.len()));
+    }
+
+    #[test]
+    fn changed_output_diverges() {
+        let image = [3, 9, 1002, 9, 2, 9, 4, 9, 99, 0];
+        let (mut session, _) = record(&image, &[vec![21]]).unwrap();
+        assert_eq!(session.to_text(), "0 in 21\n2 out 42\n3 halt\n");
+
+        session.entries[1].event = Event::Output(43);
+        let divergence = replay(&image, &session).unwrap_err();
+        assert_eq!((divergence.index, divergence.step, divergence.ip), (1, 2, 6));
+        assert_eq!(divergence.actual, Actual::Event(Event::Output(42)));
+        assert_eq!(divergence.to_string(), "event 1 at step 2 (ip 6): expected out 43 (recorded at step 2), got out 42");
+    }
+
+    #[test]
+    fn missing_input_diverges() {
+        let session = Session::parse("# no input recorded\n0 out 7\n").unwrap();
+        let divergence = replay(&[3, 0, 4, 0, 99], &session).unwrap_err();
+        assert_eq!(divergence.index, 0);
+        assert_eq!(divergence.actual, Actual::InputRequest);
+    }
+
+    #[test]
+    fn halting_early_diverges() {
+        let session = Session::parse("0 out 7\n1 out 8\n2 halt\n").unwrap();
+        let divergence = replay(&[104, 7, 99], &session).unwrap_err();
+        assert_eq!(divergence.index, 1);
+        assert_eq!(divergence.actual, Actual::Event(Event::Halt));
+    }
+
+    #[test]
+    fn invalid_entries() {
+        assert_eq!(Session::parse("0 in 1\n3 out\n"), Err("line 2: invalid entry `3 out`".to_string()));
+        assert_eq!(Session::parse("x halt"), Err("line 1: invalid entry `x halt`".to_string()));
+    }
+}
@@ -0,0 +1,402 @@
+// Second execution engine running Intcode as a table of pre-bound Rust closures.
+//
+// Instructions found by the control flow analysis are translated ahead of time, with their
+// parameter modes and values resolved into closures so running an instruction is a single indirect
+// call. Instructions only reached through indirect jumps are translated the first time they run.
+// Code that may be overwritten (a position mode write targets it) is never translated. Reaching it,
+// or any write landing in translated code, hands the program over to the interpreter for the rest
+// of the run.
+// Only the day 9 instruction set is supported, without access flags, custom opcodes or recording.
+
+use crate::intcode::cfg::{Cfg, Instruction, Operation, Param};
+use crate::intcode::vm::{Engine, ParamMode, Program, RunState, VmError, MAX_MEMORY};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+struct State {
+    memory: Vec<i64>,
+    // Whether each address belongs to a translated instruction
+    code: Vec<bool>,
+    relative_base: i64,
+    input: VecDeque<i64>,
+    output: VecDeque<i64>,
+}
+
+impl State {
+    fn read(&self, address: usize) -> i64 {
+        self.memory.get(address).cloned().unwrap_or(0)
+    }
+
+    // Returns whether the write landed in translated code
+    fn write(&mut self, address: usize, value: i64) -> bool {
+        if address >= self.memory.len() {
+            self.memory.resize(address + 1, 0);
+        }
+        self.memory[address] = value;
+        self.code.get(address) == Some(&true)
+    }
+}
+
+fn check_address(ip: usize, target: i64) -> Result<usize, VmError> {
+    if target < 0 || target as usize >= MAX_MEMORY {
+        return Err(VmError::AddressOutOfRange { address: ip, target });
+    }
+    Ok(target as usize)
+}
+
+enum Control {
+    Continue(usize),
+    NeedInput,
+    Halt,
+    // The instruction completed but modified translated code, execution continues at the address
+    // in the interpreter
+    Interpret(usize),
+}
+
+type Load = Box<dyn Fn(&State) -> Result<i64, VmError> + Send + Sync>;
+type Store = Box<dyn Fn(&mut State, i64) -> Result<bool, VmError> + Send + Sync>;
+type Op = Box<dyn Fn(&mut State) -> Result<Control, VmError> + Send + Sync>;
+
+fn load(ip: usize, param: Param) -> Load {
+    let value = param.value;
+    match param.mode {
+        ParamMode::Immediate => Box::new(move |_| Ok(value)),
+        ParamMode::Position => match check_address(ip, value) {
+            Ok(address) => Box::new(move |s| Ok(s.read(address))),
+            Err(e) => Box::new(move |_| Err(e.clone())),
+        },
+        ParamMode::Relative => Box::new(move |s| Ok(s.read(check_address(ip, s.relative_base + value)?))),
+    }
+}
+
+fn store(ip: usize, param: Param) -> Store {
+    let value = param.value;
+    match param.mode {
+        ParamMode::Immediate => Box::new(move |_, _| Err(VmError::WriteToImmediate { address: ip })),
+        ParamMode::Position => match check_address(ip, value) {
+            Ok(address) => Box::new(move |s, v| Ok(s.write(address, v))),
+            Err(e) => Box::new(move |_, _| Err(e.clone())),
+        },
+        ParamMode::Relative => Box::new(move |s, v| {
+            let address = check_address(ip, s.relative_base + value)?;
+            Ok(s.write(address, v))
+        }),
+    }
+}
+
+fn after_write(hit_code: bool, next: usize) -> Control {
+    if hit_code {
+        Control::Interpret(next)
+    } else {
+        Control::Continue(next)
+    }
+}
+
+fn translate(inst: &Instruction) -> Op {
+    let ip = inst.address;
+    let next = inst.next();
+    let p = &inst.params;
+
+    match inst.opcode {
+        Operation::Add | Operation::Mul | Operation::LessThan | Operation::Equal => {
+            let (a, b, w) = (load(ip, p[0]), load(ip, p[1]), store(ip, p[2]));
+            match inst.opcode {
+                Operation::Add => Box::new(move |s| Ok(after_write(w(s, a(s)? + b(s)?)?, next))),
+                Operation::Mul => Box::new(move |s| Ok(after_write(w(s, a(s)? * b(s)?)?, next))),
+                Operation::LessThan => Box::new(move |s| Ok(after_write(w(s, (a(s)? < b(s)?) as i64)?, next))),
+                _ => Box::new(move |s| Ok(after_write(w(s, (a(s)? == b(s)?) as i64)?, next))),
+            }
+        }
+        Operation::Input => {
+            let w = store(ip, p[0]);
+            Box::new(move |s| match s.input.pop_front() {
+                Some(value) => Ok(after_write(w(s, value)?, next)),
+                None => Ok(Control::NeedInput),
+            })
+        }
+        Operation::Output => {
+            let a = load(ip, p[0]);
+            Box::new(move |s| {
+                let value = a(s)?;
+                s.output.push_back(value);
+                Ok(Control::Continue(next))
+            })
+        }
+        Operation::JumpIfTrue | Operation::JumpIfFalse => {
+            let (condition, target) = (load(ip, p[0]), load(ip, p[1]));
+            let jump_if = inst.opcode == Operation::JumpIfTrue;
+            Box::new(move |s| {
+                if (condition(s)? != 0) == jump_if {
+                    Ok(Control::Continue(check_address(ip, target(s)?)?))
+                } else {
+                    Ok(Control::Continue(next))
+                }
+            })
+        }
+        Operation::AdjustRelativeBase => {
+            let a = load(ip, p[0]);
+            Box::new(move |s| {
+                s.relative_base += a(s)?;
+                Ok(Control::Continue(next))
+            })
+        }
+        Operation::Halt => Box::new(|_| Ok(Control::Halt)),
+    }
+}
+
+enum Slot {
+    Untranslated,
+    Translated(Op),
+    // Never translated, the interpreter takes over when execution gets there
+    Interpret,
+}
+
+pub struct ThreadedProgram {
+    state: State,
+    ops: Vec<Slot>,
+    ip: usize,
+    steps: u64,
+    halted: bool,
+    // Set once the program was handed over to the interpreter
+    interpreter: Option<Program>,
+}
+
+impl ThreadedProgram {
+    pub fn new(image: &[i64]) -> ThreadedProgram {
+        let mut program = ThreadedProgram {
+            state: State {
+                memory: image.to_vec(),
+                code: vec![false; image.len()],
+                relative_base: 0,
+                input: VecDeque::new(),
+                output: VecDeque::new(),
+            },
+            ops: (0..image.len()).map(|_| Slot::Untranslated).collect(),
+            ip: 0,
+            steps: 0,
+            halted: false,
+            interpreter: None,
+        };
+
+        let cfg = Cfg::build(image);
+        for inst in cfg.instructions.values() {
+            if cfg.is_self_modified(inst) {
+                program.ops[inst.address] = Slot::Interpret;
+            } else {
+                program.add_translation(inst);
+            }
+        }
+        program
+    }
+
+    fn add_translation(&mut self, inst: &Instruction) {
+        for address in inst.address..inst.next().min(self.state.code.len()) {
+            self.state.code[address] = true;
+        }
+        self.ops[inst.address] = Slot::Translated(translate(inst));
+    }
+
+    // Number of instructions translated to closures
+    pub fn num_translated(&self) -> usize {
+        self.ops.iter().filter(|slot| matches!(slot, Slot::Translated(_))).count()
+    }
+
+    pub fn is_interpreted(&self) -> bool {
+        self.interpreter.is_some()
+    }
+
+    fn hand_over(&mut self, ip: usize) -> &mut Program {
+        let mut program = Program::resume(self.state.memory.clone(), ip, self.state.relative_base, self.steps);
+        program.extend_input(self.state.input.drain(..));
+        self.interpreter = Some(program);
+        self.interpreter.as_mut().unwrap()
+    }
+
+    fn run_translated(&mut self) -> Result<RunState, VmError> {
+        loop {
+            let ip = self.ip;
+
+            if let Some(Slot::Untranslated) = self.ops.get(ip) {
+                match Instruction::decode(&self.state.memory, ip) {
+                    // Overlapping an already translated instruction would make writes detection unreliable
+                    Some(inst) if !self.state.code[ip..inst.next().min(self.state.code.len())].contains(&true) => self.add_translation(&inst),
+                    _ => self.ops[ip] = Slot::Interpret,
+                }
+            }
+
+            let op = match self.ops.get(ip) {
+                Some(Slot::Translated(op)) => op,
+                _ => return self.hand_over(ip).run(),
+            };
+
+            match op(&mut self.state)? {
+                Control::Continue(next) => self.ip = next,
+                Control::NeedInput => return Ok(RunState::NeedInput),
+                Control::Halt => {
+                    self.steps += 1;
+                    self.halted = true;
+                    return Ok(RunState::Halted);
+                }
+                Control::Interpret(next) => {
+                    self.steps += 1;
+                    return self.hand_over(next).run();
+                }
+            }
+            self.steps += 1;
+        }
+    }
+}
+
+impl Engine for ThreadedProgram {
+    fn push_input(&mut self, value: i64) {
+        match &mut self.interpreter {
+            Some(program) => program.push_input(value),
+            None => self.state.input.push_back(value),
+        }
+    }
+
+    fn run(&mut self) -> Result<RunState, VmError> {
+        if let Some(program) = &mut self.interpreter {
+            return program.run();
+        }
+        if self.halted {
+            return Err(VmError::AlreadyHalted);
+        }
+        self.run_translated()
+    }
+
+    fn take_output(&mut self) -> Vec<i64> {
+        let mut output: Vec<i64> = self.state.output.drain(..).collect();
+        if let Some(program) = &mut self.interpreter {
+            output.extend(program.take_output());
+        }
+        output
+    }
+
+    fn steps(&self) -> u64 {
+        match &self.interpreter {
+            Some(program) => program.steps(),
+            None => self.steps,
+        }
+    }
+}
+
+pub struct EngineTimes {
+    pub steps: u64,
+    // Instructions translated ahead of time
+    pub translated: usize,
+    pub interpreter: Duration,
+    pub threaded: Duration,
+}
+
+// Runs the program to completion on the given engine, returns the output, steps and time it took
+fn time_engine<E: Engine>(mut engine: E, input: &[i64]) -> Result<(Vec<i64>, u64, Duration), String> {
+    let start = Instant::now();
+    let state = engine.run_with_input(input).map_err(|e| e.to_string())?;
+    let elapsed = start.elapsed();
+
+    if state != RunState::Halted {
+        return Err(format!("program is {}", state));
+    }
+    Ok((engine.take_output(), engine.steps(), elapsed))
+}
+
+// Runs the program on both engines, checking they produce the same output
+pub fn compare_engines(image: &[i64], input: &[i64]) -> Result<EngineTimes, String> {
+    let (expected, steps, interpreter) = time_engine(Program::new(image), input)?;
+
+    let threaded_program = ThreadedProgram::new(image);
+    let translated = threaded_program.num_translated();
+    let (output, _, threaded) = time_engine(threaded_program, input)?;
+    if output != expected {
+        return Err(format!("outputs differ: interpreter {:?}, threaded {:?}", expected, output));
+    }
+
+    Ok(EngineTimes {
+        steps,
+        translated,
+        interpreter,
+        threaded,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intcode::loader;
+
+    // Feeds each batch of input to both engines and checks they stop in the same state with the same
+    // output and, unless they failed, step count, returns the threaded engine
+    fn compare(image: &[i64], batches: &[&[i64]]) -> ThreadedProgram {
+        let mut interpreter = Program::new(image);
+        let mut threaded = ThreadedProgram::new(image);
+        for (i, batch) in batches.iter().enumerate() {
+            let expected = Engine::run_with_input(&mut interpreter, batch);
+            assert_eq!(threaded.run_with_input(batch), expected, "batch {}", i);
+            assert_eq!(threaded.take_output(), interpreter.take_output(), "batch {}", i);
+            if expected.is_ok() {
+                assert_eq!(threaded.steps(), interpreter.steps(), "batch {}", i);
+            }
+        }
+        threaded
+    }
+
+    #[test]
+    fn day_programs_match_the_interpreter() {
+        let cases: &[(&str, &[&[i64]])] = &[
+            ("input_day5.txt", &[&[1]]),
+            ("input_day5.txt", &[&[5]]),
+            ("input_day7.txt", &[&[4], &[0]]),
+            ("input_day9.txt", &[&[1]]),
+            ("input_day11.txt", &[&[0], &[1], &[0]]),
+            ("input_day13.txt", &[&[]]),
+            ("input_day15.txt", &[&[1], &[2], &[3], &[4]]),
+            ("input_day17.txt", &[&[]]),
+        ];
+        for (path, batches) in cases {
+            compare(&loader::load_file(path).unwrap(), batches);
+        }
+    }
+
+    #[test]
+    fn day9_runs_translated() {
+        let threaded = compare(&loader::load_file("input_day9.txt").unwrap(), &[&[2]]);
+        assert!(!threaded.is_interpreted());
+    }
+
+    #[test]
+    fn overwritten_code_is_interpreted() {
+        // The first instruction clears the condition of the jump, which is never translated
+        let image = [1101, 0, 0, 5, 1105, 1, 10, 104, 7, 99, 104, 10, 99];
+        let threaded = compare(&image, &[&[]]);
+        assert!(threaded.is_interpreted());
+        assert!(matches!(threaded.ops[4], Slot::Interpret));
+    }
+
+    #[test]
+    fn writes_into_translated_code_hand_over() {
+        // A relative write changes the parameter of the output, already translated
+        let image = [109, 6, 21101, 0, 42, 4, 1105, 1, 9, 104, 7, 99];
+        let mut threaded = ThreadedProgram::new(&image);
+        assert_eq!(threaded.num_translated(), 5);
+        assert_eq!(threaded.run(), Ok(RunState::Halted));
+        assert_eq!(threaded.take_output(), vec![42]);
+        assert!(threaded.is_interpreted());
+        compare(&image, &[&[]]);
+    }
+
+    #[test]
+    fn input_after_hand_over_reaches_the_interpreter() {
+        // The jump is overwritten so the interpreter runs from it, then waits for input
+        let image = [1101, 0, 0, 5, 1105, 1, 10, 3, 20, 4, 20, 99];
+        let threaded = compare(&image, &[&[], &[8]]);
+        assert!(threaded.is_interpreted());
+    }
+
+    #[test]
+    fn errors_match_the_interpreter() {
+        compare(&[1105, 1, -1], &[&[]]);
+        compare(&[1101, 1, 1, -3, 99], &[&[]]);
+        compare(&[11101, 1, 1, 3, 99], &[&[]]);
+    }
+}
@@ -0,0 +1,422 @@
+use crate::intcode::opcode::{Flow, Opcode, Registry};
+use crate::intcode::profile::{Profile, Violation};
+use crate::intcode::session::{Event, Session};
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::Arc;
+
+// Upper bound on addressable memory, protects against programs writing to absurd addresses
+pub const MAX_MEMORY: usize = 1 << 24;
+
+// Per address access flags, tracked while the program runs
+pub const FLAG_EXECUTED: u8 = 1;
+pub const FLAG_OPERAND: u8 = 2;
+pub const FLAG_READ: u8 = 4;
+pub const FLAG_WRITTEN: u8 = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ParamMode {
+    Position,
+    Immediate,
+    Relative,
+}
+
+impl ParamMode {
+    pub fn digit(self) -> i64 {
+        match self {
+            ParamMode::Position => 0,
+            ParamMode::Immediate => 1,
+            ParamMode::Relative => 2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RunState {
+    Halted,
+    NeedInput,
+}
+
+impl fmt::Display for RunState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RunState::Halted => write!(f, "halted"),
+            RunState::NeedInput => write!(f, "waiting for input"),
+        }
+    }
+}
+
+// Common interface of the execution engines, so callers can swap the interpreter for
+// threaded::ThreadedProgram and compare them
+pub trait Engine {
+    fn push_input(&mut self, value: i64);
+
+    // Runs until the program halts or needs an input that isn't available yet
+    fn run(&mut self) -> Result<RunState, VmError>;
+
+    fn take_output(&mut self) -> Vec<i64>;
+
+    fn steps(&self) -> u64;
+
+    fn run_with_input(&mut self, input: &[i64]) -> Result<RunState, VmError> {
+        for value in input {
+            self.push_input(*value);
+        }
+        self.run()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum VmError {
+    InvalidOpcode { address: usize, value: i64 },
+    InvalidMode { address: usize, mode: i64 },
+    AddressOutOfRange { address: usize, target: i64 },
+    WriteToImmediate { address: usize },
+    Unsupported { profile: Profile, violation: Violation },
+    AlreadyHalted,
+}
+
+impl fmt::Display for VmError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VmError::InvalidOpcode { address, value } => write!(f, "invalid opcode {} at address {}", value, address),
+            VmError::InvalidMode { address, mode } => write!(f, "invalid parameter mode {} at address {}", mode, address),
+            VmError::AddressOutOfRange { address, target } => write!(f, "instruction at address {} accessed out of range address {}", address, target),
+            VmError::WriteToImmediate { address } => write!(f, "instruction at address {} writes to an immediate parameter", address),
+            VmError::Unsupported { profile, violation } => write!(f, "{} isn't supported by the {} profile", violation, profile),
+            VmError::AlreadyHalted => write!(f, "program already halted"),
+        }
+    }
+}
+
+impl std::error::Error for VmError {}
+
+#[derive(Clone)]
+pub struct Program {
+    memory: Vec<i64>,
+    flags: Vec<u8>,
+    ip: usize,
+    relative_base: i64,
+    input: VecDeque<i64>,
+    output: VecDeque<i64>,
+    halted: bool,
+    steps: u64,
+    profile: Profile,
+    strict: bool,
+    image_len: usize,
+    violations: Vec<Violation>,
+    opcodes: Arc<Registry>,
+    recording: Option<Session>,
+}
+
+impl Program {
+    pub fn new(image: &[i64]) -> Program {
+        Program::with_profile(image, Profile::Day9, true)
+    }
+
+    // With strict set, anything outside of the profile is an error, otherwise the program runs with
+    // the full instruction set and every use of a newer feature is recorded as a violation
+    pub fn with_profile(image: &[i64], profile: Profile, strict: bool) -> Program {
+        Program {
+            memory: image.to_vec(),
+            flags: vec![0; image.len()],
+            ip: 0,
+            relative_base: 0,
+            input: VecDeque::new(),
+            output: VecDeque::new(),
+            halted: false,
+            steps: 0,
+            profile,
+            strict,
+            image_len: image.len(),
+            violations: Vec::new(),
+            opcodes: Arc::new(Registry::builtin()),
+            recording: None,
+        }
+    }
+
+    // Continues from the state of a program run by another engine, with the day 9 instruction set
+    pub fn resume(memory: Vec<i64>, ip: usize, relative_base: i64, steps: u64) -> Program {
+        let mut program = Program::new(&[]);
+        program.flags = vec![0; memory.len()];
+        program.memory = memory;
+        program.ip = ip;
+        program.relative_base = relative_base;
+        program.steps = steps;
+        program
+    }
+
+    pub fn memory(&self) -> &[i64] {
+        &self.memory
+    }
+
+    pub fn flags(&self) -> &[u8] {
+        &self.flags
+    }
+
+    pub fn ip(&self) -> usize {
+        self.ip
+    }
+
+    pub fn relative_base(&self) -> i64 {
+        self.relative_base
+    }
+
+    pub fn opcodes(&self) -> &Registry {
+        &self.opcodes
+    }
+
+    // Adds or replaces an opcode on this instance only, returns the opcode previously using that number
+    pub fn register_opcode<O: Opcode + 'static>(&mut self, opcode: O) -> Option<Arc<dyn Opcode>> {
+        Arc::make_mut(&mut self.opcodes).register(Arc::new(opcode))
+    }
+
+    pub fn profile(&self) -> Profile {
+        self.profile
+    }
+
+    pub fn violations(&self) -> &[Violation] {
+        &self.violations
+    }
+
+    // Number of instructions executed so far
+    pub fn steps(&self) -> u64 {
+        self.steps
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted
+    }
+
+    // Logs every input consumed and output produced from now on, see session::replay
+    pub fn start_recording(&mut self) {
+        self.recording = Some(Session::default());
+    }
+
+    pub fn take_recording(&mut self) -> Option<Session> {
+        self.recording.take()
+    }
+
+    fn record(&mut self, event: Event) {
+        if let Some(session) = &mut self.recording {
+            session.push(self.steps, event);
+        }
+    }
+
+    // Memory is unbounded (up to MAX_MEMORY), anything never written reads as 0
+    pub fn read(&self, address: usize) -> i64 {
+        self.memory.get(address).cloned().unwrap_or(0)
+    }
+
+    fn grow(&mut self, address: usize) {
+        if address >= self.memory.len() {
+            self.memory.resize(address + 1, 0);
+            self.flags.resize(address + 1, 0);
+        }
+    }
+
+    pub fn write(&mut self, address: usize, value: i64) {
+        self.grow(address);
+        self.memory[address] = value;
+    }
+
+    fn mark(&mut self, address: usize, flag: u8) {
+        self.grow(address);
+        self.flags[address] |= flag;
+    }
+
+    fn mark_instruction(&mut self, num_params: usize) {
+        self.mark(self.ip, FLAG_EXECUTED);
+        for i in 1..=num_params {
+            self.mark(self.ip + i, FLAG_OPERAND);
+        }
+    }
+
+    pub fn push_input(&mut self, value: i64) {
+        self.input.push_back(value);
+    }
+
+    pub fn extend_input<I: IntoIterator<Item = i64>>(&mut self, values: I) {
+        self.input.extend(values);
+    }
+
+    pub fn pop_input(&mut self) -> Option<i64> {
+        let value = self.input.pop_front()?;
+        self.record(Event::Input(value));
+        Some(value)
+    }
+
+    pub fn push_output(&mut self, value: i64) {
+        self.record(Event::Output(value));
+        self.output.push_back(value);
+    }
+
+    pub fn adjust_relative_base(&mut self, offset: i64) {
+        self.relative_base += offset;
+    }
+
+    pub fn pending_input(&self) -> &VecDeque<i64> {
+        &self.input
+    }
+
+    pub fn output(&self) -> &VecDeque<i64> {
+        &self.output
+    }
+
+    pub fn pop_output(&mut self) -> Option<i64> {
+        self.output.pop_front()
+    }
+
+    pub fn take_output(&mut self) -> Vec<i64> {
+        self.output.drain(..).collect()
+    }
+
+    fn param_mode(&self, param_index: usize) -> Result<ParamMode, VmError> {
+        match (self.read(self.ip) / 10i64.pow((param_index + 1) as u32)) % 10 {
+            0 => Ok(ParamMode::Position),
+            1 => Ok(ParamMode::Immediate),
+            2 => Ok(ParamMode::Relative),
+            mode => Err(VmError::InvalidMode { address: self.ip, mode }),
+        }
+    }
+
+    fn violate(&mut self, violation: Violation) -> Result<(), VmError> {
+        if self.strict {
+            return Err(VmError::Unsupported { profile: self.profile, violation });
+        }
+        if !self.violations.contains(&violation) {
+            self.violations.push(violation);
+        }
+        Ok(())
+    }
+
+    fn checked_mode(&mut self, param_index: usize) -> Result<ParamMode, VmError> {
+        let mode = self.param_mode(param_index)?;
+        if !self.profile.supports_mode(mode) {
+            self.violate(Violation::Mode { address: self.ip, mode })?;
+        }
+        Ok(mode)
+    }
+
+    fn check_address(&mut self, target: i64) -> Result<usize, VmError> {
+        if target < 0 || target as usize >= MAX_MEMORY {
+            return Err(VmError::AddressOutOfRange { address: self.ip, target });
+        }
+        if target as usize >= self.image_len && !self.profile.growable_memory() {
+            self.violate(Violation::MemoryGrowth {
+                address: self.ip,
+                target: target as usize,
+            })?;
+        }
+        Ok(target as usize)
+    }
+
+    // Value of a parameter (starting at 1) of the current instruction
+    pub fn get_param(&mut self, param_index: usize) -> Result<i64, VmError> {
+        let value = self.read(self.ip + param_index);
+        let address = match self.checked_mode(param_index)? {
+            ParamMode::Position => self.check_address(value)?,
+            ParamMode::Immediate => return Ok(value),
+            ParamMode::Relative => self.check_address(value + self.relative_base)?,
+        };
+        self.mark(address, FLAG_READ);
+        Ok(self.read(address))
+    }
+
+    pub fn set_param(&mut self, param_index: usize, to_store: i64) -> Result<(), VmError> {
+        let value = self.read(self.ip + param_index);
+        let address = match self.checked_mode(param_index)? {
+            ParamMode::Position => self.check_address(value)?,
+            ParamMode::Immediate => return Err(VmError::WriteToImmediate { address: self.ip }),
+            ParamMode::Relative => self.check_address(value + self.relative_base)?,
+        };
+        self.write(address, to_store);
+        self.mark(address, FLAG_WRITTEN);
+        Ok(())
+    }
+
+    // Executes a single instruction, returns the new state if execution can't continue
+    pub fn step(&mut self) -> Result<Option<RunState>, VmError> {
+        if self.halted {
+            return Err(VmError::AlreadyHalted);
+        }
+
+        let op = self.read(self.ip);
+        let code = op % 100;
+        if Profile::Day9.supports_opcode(code) && !self.profile.supports_opcode(code) {
+            self.violate(Violation::Opcode { address: self.ip, opcode: code })?;
+        }
+
+        let opcode = match self.opcodes.get(code) {
+            Some(opcode) => Arc::clone(opcode),
+            None => return Err(VmError::InvalidOpcode { address: self.ip, value: op }),
+        };
+
+        let num_params = opcode.num_params();
+        for i in 1..=num_params {
+            let mode = self.param_mode(i)?;
+            if !opcode.accepts_mode(i, mode) {
+                if mode == ParamMode::Immediate && opcode.write_params().contains(&i) {
+                    return Err(VmError::WriteToImmediate { address: self.ip });
+                }
+                return Err(VmError::InvalidMode { address: self.ip, mode: mode.digit() });
+            }
+        }
+
+        let flow = opcode.execute(self)?;
+        if flow == Flow::NeedInput {
+            return Ok(Some(RunState::NeedInput));
+        }
+
+        if flow == Flow::Halt {
+            self.record(Event::Halt);
+        }
+
+        self.mark_instruction(num_params);
+        self.steps += 1;
+
+        match flow {
+            Flow::Next => self.ip += num_params + 1,
+            Flow::Jump(target) => self.ip = self.check_address(target)?,
+            Flow::Halt => {
+                self.halted = true;
+                return Ok(Some(RunState::Halted));
+            }
+            Flow::NeedInput => unreachable!(),
+        }
+
+        Ok(None)
+    }
+
+    // Runs until the program halts or needs an input that isn't available yet
+    pub fn run(&mut self) -> Result<RunState, VmError> {
+        loop {
+            if let Some(state) = self.step()? {
+                return Ok(state);
+            }
+        }
+    }
+
+    pub fn run_with_input(&mut self, input: &[i64]) -> Result<RunState, VmError> {
+        self.extend_input(input.iter().cloned());
+        self.run()
+    }
+}
+
+impl Engine for Program {
+    fn push_input(&mut self, value: i64) {
+        Program::push_input(self, value)
+    }
+
+    fn run(&mut self) -> Result<RunState, VmError> {
+        Program::run(self)
+    }
+
+    fn take_output(&mut self) -> Vec<i64> {
+        Program::take_output(self)
+    }
+
+    fn steps(&self) -> u64 {
+        Program::steps(self)
+    }
+}
@@ -0,0 +1,51 @@
+// Minimal JSON rendering for the machine readable reports.
+
+use crate::solution::Answer;
+
+pub fn string(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if (c as u32) < 0x20 => quoted += &format!("\\u{:04x}", c as u32),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn rows(text: &str) -> String {
+    let rows: Vec<String> = text.lines().map(string).collect();
+    format!("[{}]", rows.join(", "))
+}
+
+// Numbers stay numbers, images become an array with one string per row and letters read from an
+// image an object with the `text` and the `image` rows
+pub fn answer(answer: &Answer) -> String {
+    match answer {
+        Answer::Number(n) => n.to_string(),
+        Answer::Text(text) => string(text),
+        Answer::Image(image) => rows(&image.to_string()),
+        Answer::Lettering { text, image } => object(&[("text", Some(string(text))), ("image", Some(rows(&image.to_string())))]),
+    }
+}
+
+// Recorded answer, rendered like the answer it is compared with
+pub fn expected(answer: &Answer, expected: &str) -> String {
+    match answer {
+        Answer::Number(_) if expected.parse::<i64>().is_ok() => expected.to_string(),
+        Answer::Image(_) => rows(expected),
+        _ => string(expected),
+    }
+}
+
+// Object from already rendered values, None values are left out
+pub fn object(fields: &[(&str, Option<String>)]) -> String {
+    let fields: Vec<String> = fields.iter().filter_map(|(key, value)| value.as_ref().map(|v| format!("{}: {}", string(key), v))).collect();
+    format!("{{{}}}", fields.join(", "))
+}
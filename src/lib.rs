@@ -0,0 +1,56 @@
+// Solutions of the days and the tools built around them, usable from other crates.
+//
+// Every day exposes its `Solution` implementation and the functions of its two parts, the Intcode
+// VM and its tooling live under `intcode`, and `grid`, `parse` and `search` hold the helpers the days
+// share. Nothing here parses command line arguments or prints results, the `advent_of_code` binary
+// is the command line front end over `runner`, `bench`, `animation`, `export` and `intcode`.
+
+#[allow(non_snake_case)]
+pub mod Day1;
+#[allow(non_snake_case)]
+pub mod Day10;
+#[allow(non_snake_case)]
+pub mod Day11;
+#[allow(non_snake_case)]
+pub mod Day12;
+#[allow(non_snake_case)]
+pub mod Day13;
+#[allow(non_snake_case)]
+pub mod Day14;
+#[allow(non_snake_case)]
+pub mod Day15;
+#[allow(non_snake_case)]
+pub mod Day16;
+#[allow(non_snake_case)]
+pub mod Day17;
+#[allow(non_snake_case)]
+pub mod Day2;
+#[allow(non_snake_case)]
+pub mod Day3;
+#[allow(non_snake_case)]
+pub mod Day4;
+#[allow(non_snake_case)]
+pub mod Day5;
+#[allow(non_snake_case)]
+pub mod Day6;
+#[allow(non_snake_case)]
+pub mod Day7;
+#[allow(non_snake_case)]
+pub mod Day8;
+#[allow(non_snake_case)]
+pub mod Day9;
+pub mod animation;
+pub mod answers;
+pub mod bench;
+pub mod export;
+pub mod grid;
+pub mod intcode;
+pub mod json;
+pub mod ocr;
+pub mod parse;
+pub mod runner;
+pub mod search;
+pub mod solution;
+pub mod terminal;
+#[cfg(test)]
+mod testing;
@@ -1,7 +1,148 @@
-#[allow(non_snake_case)]
-mod Day17;
+mod cli;
+
+use cli::{animate, bench, export, intcode, run};
+use std::env;
+use std::process;
 
 fn main() {
-    Day17::part1::run_puzzle();
-    Day17::part2::run_puzzle();
+    let args: Vec<String> = env::args().collect();
+
+    if args.len() > 1 && args[1] == "intcode-test" {
+        if args.len() != 4 {
+            eprintln!("Usage: {} intcode-test <program> <cases_dir>", args[0]);
+            process::exit(2);
+        }
+        match intcode::test_command(&args[2], &args[3]) {
+            Ok(true) => process::exit(0),
+            Ok(false) => process::exit(1),
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(2);
+            }
+        }
+    }
+
+    if args.len() > 1 && args[1] == "intcode-dump" {
+        if args.len() < 3 {
+            eprintln!("Usage: {} intcode-dump <program> [inputs...]", args[0]);
+            process::exit(2);
+        }
+        if let Err(e) = intcode::dump_command(&args[2], &args[3..]) {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "intcode-optimise" {
+        if args.len() < 4 {
+            eprintln!("Usage: {} intcode-optimise <program> <output> [sample inputs...]", args[0]);
+            process::exit(2);
+        }
+        if let Err(e) = intcode::optimise_command(&args[2], &args[3], &args[4..]) {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "intcode-compile" {
+        if args.len() != 4 {
+            eprintln!("Usage: {} intcode-compile <source> <output>", args[0]);
+            process::exit(2);
+        }
+        if let Err(e) = intcode::compile_command(&args[2], &args[3]) {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "intcode-record" {
+        if args.len() < 4 {
+            eprintln!("Usage: {} intcode-record <program> <session> [inputs...]", args[0]);
+            process::exit(2);
+        }
+        if let Err(e) = intcode::record_command(&args[2], &args[3], &args[4..]) {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "intcode-replay" {
+        if args.len() != 4 {
+            eprintln!("Usage: {} intcode-replay <program> <session>", args[0]);
+            process::exit(2);
+        }
+        match intcode::replay_command(&args[2], &args[3]) {
+            Ok(true) => process::exit(0),
+            Ok(false) => process::exit(1),
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(2);
+            }
+        }
+    }
+
+    if args.len() > 1 && args[1] == "intcode-bench" {
+        if args.len() < 3 {
+            eprintln!("Usage: {} intcode-bench <program> [inputs...]", args[0]);
+            process::exit(2);
+        }
+        if let Err(e) = intcode::bench_command(&args[2], &args[3..]) {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "run" {
+        match run::run_command(&args[2..]) {
+            Ok(true) => return,
+            Ok(false) => process::exit(1),
+            Err(e) => {
+                eprintln!("{}", e);
+                process::exit(2);
+            }
+        }
+    }
+
+    if args.len() > 1 && args[1] == "bench" {
+        if let Err(e) = bench::bench_command(&args[2..]) {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "animate" {
+        if let Err(e) = animate::animate_command(&args[2..]) {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "export" {
+        if let Err(e) = export::export_command(&args[2..]) {
+            eprintln!("{}", e);
+            process::exit(2);
+        }
+        return;
+    }
+
+    eprintln!("Usage: {} <command> [args...]", args[0]);
+    eprintln!("  run [days...] [--part N] [--input PATH] [--record] [--format text|json] [--visualize] [--fps N]");
+    eprintln!("  bench [days...] [--part N] [--input PATH] [--runs N] [--csv PATH] [--json PATH]");
+    eprintln!("  animate <day> <output.gif|dir> [--input PATH] [--every N] [--scale N] [--delay MS]");
+    eprintln!("  export <day> <output.ppm|png|svg> [--input PATH] [--scale N]");
+    eprintln!("  intcode-test <program> <cases_dir>");
+    eprintln!("  intcode-dump <program> [inputs...]");
+    eprintln!("  intcode-optimise <program> <output> [sample inputs...]");
+    eprintln!("  intcode-compile <source> <output>");
+    eprintln!("  intcode-record <program> <session> [inputs...]");
+    eprintln!("  intcode-replay <program> <session>");
+    eprintln!("  intcode-bench <program> [inputs...]");
+    process::exit(2);
 }
@@ -0,0 +1,227 @@
+// Reading of the capital letters some puzzles draw with lit pixels.
+//
+// Letters are 6 pixels tall and drawn every 5 columns, or 10 pixels tall and drawn every 8. Glyphs
+// are cut at that pitch and compared without their blank borders, the first cut at which every
+// glyph is a letter wins so the position of the text in the image does not matter. Blank columns
+// can't be used to separate the letters, the small `Y` is as wide as its cell and touches the next
+// letter.
+
+use crate::solution::{Answer, Image, SolveError};
+use std::fmt;
+
+type Font = &'static [(char, &'static [&'static str])];
+
+const SMALL_FONT: Font = &[
+    ('A', &[".##.", "#..#", "#..#", "####", "#..#", "#..#"]),
+    ('B', &["###.", "#..#", "###.", "#..#", "#..#", "###."]),
+    ('C', &[".##.", "#..#", "#...", "#...", "#..#", ".##."]),
+    ('E', &["####", "#...", "###.", "#...", "#...", "####"]),
+    ('F', &["####", "#...", "###.", "#...", "#...", "#..."]),
+    ('G', &[".##.", "#..#", "#...", "#.##", "#..#", ".###"]),
+    ('H', &["#..#", "#..#", "####", "#..#", "#..#", "#..#"]),
+    ('I', &["###", ".#.", ".#.", ".#.", ".#.", "###"]),
+    ('J', &["..##", "...#", "...#", "...#", "#..#", ".##."]),
+    ('K', &["#..#", "#.#.", "##..", "#.#.", "#.#.", "#..#"]),
+    ('L', &["#...", "#...", "#...", "#...", "#...", "####"]),
+    ('O', &[".##.", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('P', &["###.", "#..#", "#..#", "###.", "#...", "#..."]),
+    ('R', &["###.", "#..#", "#..#", "###.", "#.#.", "#..#"]),
+    ('S', &[".###", "#...", "#...", ".##.", "...#", "###."]),
+    ('U', &["#..#", "#..#", "#..#", "#..#", "#..#", ".##."]),
+    ('Y', &["#...#", "#...#", ".#.#.", "..#..", "..#..", "..#.."]),
+    ('Z', &["####", "...#", "..#.", ".#..", "#...", "####"]),
+];
+
+const LARGE_FONT: Font = &[
+    ('A', &["..##..", ".#..#.", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#"]),
+    ('B', &["#####.", "#....#", "#....#", "#....#", "#####.", "#....#", "#....#", "#....#", "#....#", "#####."]),
+    ('C', &[".####.", "#....#", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#....#", ".####."]),
+    ('E', &["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('F', &["######", "#.....", "#.....", "#.....", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('G', &[".####.", "#....#", "#.....", "#.....", "#.....", "#..###", "#....#", "#....#", "#...##", ".###.#"]),
+    ('H', &["#....#", "#....#", "#....#", "#....#", "######", "#....#", "#....#", "#....#", "#....#", "#....#"]),
+    ('J', &["...###", "....#.", "....#.", "....#.", "....#.", "....#.", "....#.", "#...#.", "#...#.", ".###.."]),
+    ('K', &["#....#", "#...#.", "#..#..", "#.#...", "##....", "##....", "#.#...", "#..#..", "#...#.", "#....#"]),
+    ('L', &["#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "#.....", "######"]),
+    ('N', &["#....#", "##...#", "##...#", "#.#..#", "#.#..#", "#..#.#", "#..#.#", "#...##", "#...##", "#....#"]),
+    ('P', &["#####.", "#....#", "#....#", "#....#", "#####.", "#.....", "#.....", "#.....", "#.....", "#....."]),
+    ('R', &["#####.", "#....#", "#....#", "#....#", "#####.", "#..#..", "#...#.", "#...#.", "#....#", "#....#"]),
+    ('X', &["#....#", "#....#", ".#..#.", ".#..#.", "..##..", "..##..", ".#..#.", ".#..#.", "#....#", "#....#"]),
+    ('Z', &["######", ".....#", ".....#", "....#.", "...#..", "..#...", ".#....", "#.....", "#.....", "######"]),
+];
+
+#[derive(Debug, PartialEq)]
+pub enum OcrError {
+    // Height of the lit rows, matching no font
+    Height(usize),
+    // Glyph drawn with `#` and `.`, starting at `column` of the image
+    Glyph { column: usize, glyph: Vec<String> },
+}
+
+impl fmt::Display for OcrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OcrError::Height(height) => write!(f, "No font has letters {} pixels tall", height),
+            OcrError::Glyph { column, glyph } => write!(f, "Unrecognised glyph at column {}:\n{}", column, glyph.join("\n")),
+        }
+    }
+}
+
+impl std::error::Error for OcrError {}
+
+// Pattern without its blank columns on the left and right
+fn trim_columns(pattern: &[&str]) -> Vec<String> {
+    let lit = |x: usize| pattern.iter().any(|row| row.as_bytes()[x] == b'#');
+    let width = pattern[0].len();
+    let start = (0..width).find(|&x| lit(x)).unwrap_or(0);
+    let end = (0..width).rev().find(|&x| lit(x)).map_or(start, |x| x + 1);
+    pattern.iter().map(|row| row[start..end].to_string()).collect()
+}
+
+fn recognise(font: Font, glyph: &[String]) -> Option<char> {
+    font.iter().find(|(_, pattern)| trim_columns(pattern) == glyph).map(|(letter, _)| *letter)
+}
+
+// Glyph in the columns `start..start + width` of the rows `top..=bottom`, columns outside the
+// image being blank
+fn glyph(image: &Image, top: usize, bottom: usize, start: i64, width: usize) -> Vec<String> {
+    let lit = |x: i64, y: usize| x >= 0 && (x as usize) < image.width && image.get(x as usize, y);
+    (top..=bottom).map(|y| (start..start + width as i64).map(|x| if lit(x, y) { '#' } else { '.' }).collect()).collect()
+}
+
+// Text drawn in the image, empty when nothing is lit
+pub fn read(image: &Image) -> Result<String, OcrError> {
+    let rows: Vec<usize> = (0..image.height).filter(|&y| (0..image.width).any(|x| image.get(x, y))).collect();
+    let (top, bottom) = match (rows.first(), rows.last()) {
+        (Some(&top), Some(&bottom)) => (top, bottom),
+        _ => return Ok(String::new()),
+    };
+
+    let (font, pitch) = match bottom - top + 1 {
+        6 => (SMALL_FONT, 5),
+        10 => (LARGE_FONT, 8),
+        height => return Err(OcrError::Height(height)),
+    };
+
+    let lit_column = |x: usize| (top..=bottom).any(|y| image.get(x, y));
+    let first = (0..image.width).find(|&x| lit_column(x)).unwrap() as i64;
+    let last = (0..image.width).rev().find(|&x| lit_column(x)).unwrap() as i64;
+
+    // The first lit column is somewhere in the first cell, the error kept is the one of the cut
+    // reading the most letters
+    let mut furthest: Option<(usize, Vec<String>)> = None;
+    for offset in (first - (pitch as i64 - 1))..=first {
+        let mut text = String::new();
+        let mut start = offset;
+
+        while start <= last {
+            let cell = glyph(image, top, bottom, start, pitch);
+            let trimmed = trim_columns(&cell.iter().map(String::as_str).collect::<Vec<_>>());
+            if !trimmed[0].is_empty() {
+                match recognise(font, &trimmed) {
+                    Some(letter) => text.push(letter),
+                    None => {
+                        let column = start.max(0) as usize;
+                        if furthest.as_ref().is_none_or(|(c, _)| *c < column) {
+                            furthest = Some((column, trimmed));
+                        }
+                        break;
+                    }
+                }
+            }
+            start += pitch as i64;
+        }
+
+        if start > last {
+            return Ok(text);
+        }
+    }
+
+    let (column, glyph) = furthest.unwrap();
+    Err(OcrError::Glyph { column, glyph })
+}
+
+// Letters read from the image kept along with it, an error showing the glyph that can't be read
+pub fn answer(image: Image) -> Result<Answer, SolveError> {
+    match read(&image) {
+        Ok(text) if text.is_empty() => Err(SolveError::Failed(String::from("No letters in the image"))),
+        Ok(text) => Ok(Answer::Lettering { text, image }),
+        Err(e) => Err(SolveError::Failed(e.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Word drawn with the letters of the font every `pitch` columns, starting at column `left`
+    fn draw(font: Font, pitch: usize, word: &str, left: usize) -> Image {
+        let height = font[0].1.len();
+        let mut image = Image::new(left + word.len() * pitch + 3, height + 2);
+        for (i, letter) in word.chars().enumerate() {
+            let (_, pattern) = font.iter().find(|(l, _)| *l == letter).unwrap();
+            for (y, row) in pattern.iter().enumerate() {
+                for (x, pixel) in row.chars().enumerate() {
+                    image.set(left + i * pitch + x, y + 1, pixel == '#');
+                }
+            }
+        }
+        image
+    }
+
+    #[test]
+    fn reads_words_in_the_small_font() {
+        for &word in &["ACKPZ", "CGPJCGCL", "BEFHILORSU"] {
+            for left in 0..5 {
+                assert_eq!(read(&draw(SMALL_FONT, 5, word, left)), Ok(String::from(word)));
+            }
+        }
+    }
+
+    #[test]
+    fn reads_a_small_y_touching_the_next_letter() {
+        for &word in &["YES", "KEYS", "JOYFUL", "YY"] {
+            for left in 0..5 {
+                assert_eq!(read(&draw(SMALL_FONT, 5, word, left)), Ok(String::from(word)));
+            }
+        }
+    }
+
+    #[test]
+    fn reads_words_in_the_large_font() {
+        for &word in &["ZEBRA", "FLANK", "GRAPH", "JCX"] {
+            for left in 0..8 {
+                assert_eq!(read(&draw(LARGE_FONT, 8, word, left)), Ok(String::from(word)));
+            }
+        }
+    }
+
+    #[test]
+    fn blank_images_read_as_nothing() {
+        assert_eq!(read(&Image::new(10, 6)), Ok(String::new()));
+    }
+
+    #[test]
+    fn unknown_glyphs_and_heights_are_errors() {
+        let mut image = draw(SMALL_FONT, 5, "AB", 0);
+        image.set(6, 2, true);
+        assert!(matches!(read(&image), Err(OcrError::Glyph { column: 5, .. })));
+
+        let mut image = Image::new(4, 5);
+        image.set(0, 0, true);
+        image.set(0, 4, true);
+        assert_eq!(read(&image), Err(OcrError::Height(5)));
+    }
+
+    #[test]
+    fn answers_keep_the_image_or_report_the_glyph() {
+        assert!(matches!(answer(draw(SMALL_FONT, 5, "HO", 1)), Ok(Answer::Lettering { ref text, .. }) if text == "HO"));
+
+        let mut image = draw(SMALL_FONT, 5, "HO", 0);
+        image.set(6, 3, true);
+        let error = answer(image).unwrap_err().to_string();
+        assert_eq!(error, "Unrecognised glyph at column 5:\n.##.\n#..#\n##.#\n#..#\n#..#\n.##.");
+
+        assert_eq!(answer(Image::new(4, 4)).unwrap_err().to_string(), "No letters in the image");
+    }
+}
@@ -0,0 +1,210 @@
+// Shared parsing of the puzzle inputs.
+//
+// Inputs are cut into spans, pieces of text remembering the line and column (both starting at 1) they
+// come from, so every error points at the offending spot. The runner adds the name of the file.
+// Blank lines are ignored everywhere, which takes care of trailing newlines.
+
+use crate::intcode::loader::{self, LoadErrorKind};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParseError {
+    pub path: Option<String>,
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl ParseError {
+    pub fn with_path(mut self, path: &str) -> ParseError {
+        self.path = Some(path.to_string());
+        self
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let source = self.path.as_deref().unwrap_or("<input>");
+        write!(f, "{}:{}:{}: {}", source, self.line, self.column, self.message)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Span<'a> {
+    pub text: &'a str,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl<'a> Span<'a> {
+    pub fn error<S: Into<String>>(&self, message: S) -> ParseError {
+        ParseError {
+            path: None,
+            line: self.line,
+            column: self.column,
+            message: message.into(),
+        }
+    }
+
+    // Part of the span between two byte offsets
+    fn slice(self, start: usize, end: usize) -> Span<'a> {
+        Span {
+            text: &self.text[start..end],
+            line: self.line,
+            column: self.column + self.text[..start].chars().count(),
+        }
+    }
+
+    pub fn trim(self) -> Span<'a> {
+        let start = self.text.len() - self.text.trim_start().len();
+        let end = self.text.trim_end().len().max(start);
+        self.slice(start, end)
+    }
+
+    pub fn split(self, separator: &str) -> Vec<Span<'a>> {
+        let mut spans = Vec::new();
+        let mut start = 0;
+        while let Some(pos) = self.text[start..].find(separator) {
+            spans.push(self.slice(start, start + pos));
+            start += pos + separator.len();
+        }
+        spans.push(self.slice(start, self.text.len()));
+        spans
+    }
+
+    pub fn split_once(self, separator: &str) -> Result<(Span<'a>, Span<'a>), ParseError> {
+        match self.text.find(separator) {
+            Some(pos) => Ok((self.slice(0, pos), self.slice(pos + separator.len(), self.text.len()))),
+            None => Err(self.error(format!("expected `{}` in `{}`", separator, self.text))),
+        }
+    }
+
+    pub fn strip_prefix(self, prefix: &str) -> Result<Span<'a>, ParseError> {
+        if self.text.starts_with(prefix) {
+            Ok(self.slice(prefix.len(), self.text.len()))
+        } else {
+            Err(self.error(format!("expected `{}`", prefix)))
+        }
+    }
+
+    pub fn strip_suffix(self, suffix: &str) -> Result<Span<'a>, ParseError> {
+        if self.text.ends_with(suffix) {
+            Ok(self.slice(0, self.text.len() - suffix.len()))
+        } else {
+            let end = self.slice(self.text.len(), self.text.len());
+            Err(end.error(format!("expected `{}`", suffix)))
+        }
+    }
+
+    pub fn not_empty(self, what: &str) -> Result<Span<'a>, ParseError> {
+        if self.text.is_empty() {
+            Err(self.error(format!("missing {}", what)))
+        } else {
+            Ok(self)
+        }
+    }
+
+    pub fn integer<T: FromStr>(self) -> Result<T, ParseError> {
+        self.text.parse().map_err(|_| self.error(format!("invalid integer `{}`", self.text)))
+    }
+
+    // Fails on the first character not in `allowed`
+    pub fn check_chars(self, allowed: &str, what: &str) -> Result<Span<'a>, ParseError> {
+        match self.text.char_indices().find(|(_, c)| !allowed.contains(*c)) {
+            Some((pos, c)) => Err(self.slice(pos, pos).error(format!("invalid {} `{}`", what, c))),
+            None => Ok(self),
+        }
+    }
+}
+
+// Non blank lines of the input, untrimmed
+pub fn lines(input: &str) -> impl Iterator<Item = Span<'_>> {
+    input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, text)| Span { text, line: index + 1, column: 1 })
+}
+
+// The input as a whole, for the errors about all of it
+pub fn whole(input: &str) -> Span<'_> {
+    Span { text: input, line: 1, column: 1 }
+}
+
+// The only non blank line of the input, trimmed
+pub fn single_line(input: &str) -> Result<Span<'_>, ParseError> {
+    let mut lines = lines(input);
+    let line = lines.next().ok_or_else(|| whole(input).error("empty input"))?;
+    match lines.next() {
+        Some(extra) => Err(extra.error("expected a single line")),
+        None => Ok(line.trim()),
+    }
+}
+
+// One integer per line
+pub fn integer_lines<T: FromStr>(input: &str) -> Result<Vec<T>, ParseError> {
+    lines(input).map(|line| line.trim().integer()).collect()
+}
+
+// A line of decimal digits
+pub fn digits(input: &str) -> Result<Vec<i64>, ParseError> {
+    let line = single_line(input)?.check_chars("0123456789", "digit")?;
+    Ok(line.text.chars().map(|c| c.to_digit(10).unwrap() as i64).collect())
+}
+
+// Rectangular grid of characters, `cell` returns None for the invalid ones
+pub fn grid<T, F: Fn(char) -> Option<T>>(input: &str, cell: F) -> Result<Vec<Vec<T>>, ParseError> {
+    let mut rows: Vec<Vec<T>> = Vec::new();
+    for line in lines(input) {
+        let line = line.trim();
+        let mut row = Vec::new();
+        for (pos, c) in line.text.char_indices() {
+            row.push(cell(c).ok_or_else(|| line.slice(pos, pos).error(format!("invalid cell `{}`", c)))?);
+        }
+        if let Some(first) = rows.first() {
+            if row.len() != first.len() {
+                return Err(line.error(format!("row has {} cells, expected {}", row.len(), first.len())));
+            }
+        }
+        rows.push(row);
+    }
+    Ok(rows)
+}
+
+// Two non empty values around a separator, like `COM)B` or `264360-746325`
+pub fn pair<'a>(span: Span<'a>, separator: &str) -> Result<(Span<'a>, Span<'a>), ParseError> {
+    let (left, right) = span.trim().split_once(separator)?;
+    Ok((left.trim().not_empty("value")?, right.trim().not_empty("value")?))
+}
+
+// Quantity and name, like `7 ORE`
+pub fn term(span: Span) -> Result<(i64, Span), ParseError> {
+    let span = span.trim();
+    let (quantity, name) = span.split_once(" ").map_err(|_| span.error(format!("expected a quantity and a name in `{}`", span.text)))?;
+    Ok((quantity.integer()?, name.trim().not_empty("name")?))
+}
+
+// Direction letter among `directions` followed by a distance, like `R75`
+pub fn direction_distance(span: Span, directions: &str) -> Result<(char, i64), ParseError> {
+    let span = span.trim().not_empty("move")?;
+    let direction = span.text.chars().next().unwrap();
+    if !directions.contains(direction) {
+        return Err(span.error(format!("invalid direction `{}`, expected one of {}", direction, directions)));
+    }
+    Ok((direction, span.slice(direction.len_utf8(), span.text.len()).integer()?))
+}
+
+// Intcode program in the text format of the loader
+pub fn intcode(input: &str) -> Result<Vec<i64>, ParseError> {
+    let image = loader::parse_text(input).map_err(|e| match e.kind {
+        LoadErrorKind::Parse { line, column, token } => Span { text: "", line, column }.error(format!("invalid value `{}`", token)),
+        _ => whole("").error(e.to_string()),
+    })?;
+    if image.is_empty() {
+        return Err(whole("").error("the program is empty"));
+    }
+    Ok(image)
+}
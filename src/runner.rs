@@ -0,0 +1,178 @@
+// Registry of the puzzle solutions and of where their input comes from.
+//
+// Every day is registered with the functions solving, drawing and animating it from its input text.
+// Inputs are read from a file named after the day, days 4, 12 and 16 embed theirs in their module.
+// An input can be overridden by any path, `-` reading it from stdin.
+
+use crate::animation::Recorder;
+use crate::export::Raster;
+use crate::intcode::session::Sessions;
+use crate::parse::ParseError;
+use crate::solution::{self, SolveError, SolveResult};
+use crate::{Day1, Day10, Day11, Day12, Day13, Day14, Day15, Day16, Day17, Day2, Day3, Day4, Day5, Day6, Day7, Day8, Day9};
+use std::fs;
+use std::io::{self, Read};
+
+pub enum Input {
+    File(&'static str),
+    Embedded(&'static str),
+}
+
+pub struct Day {
+    pub number: u32,
+    pub input: Input,
+    // Parses the input and solves the given parts, recording the Intcode programs in the sessions
+    pub solve: fn(&str, &[usize], &mut Sessions) -> Result<SolveResult, ParseError>,
+    // Parses the input and draws it, None for the days without a picture
+    pub picture: fn(&str) -> Result<Option<Raster>, SolveError>,
+    // Parses the input and records the frames of the simulation, false for the days without one
+    pub animate: fn(&str, &mut Recorder) -> Result<bool, SolveError>,
+}
+
+pub const DAYS: [Day; 17] = [
+    Day {
+        number: 1,
+        input: Input::File("input_day1.txt"),
+        solve: solution::solve::<Day1::Day1>,
+        picture: solution::picture::<Day1::Day1>,
+        animate: solution::animate::<Day1::Day1>,
+    },
+    Day {
+        number: 2,
+        input: Input::File("input_day2.txt"),
+        solve: solution::solve::<Day2::Day2>,
+        picture: solution::picture::<Day2::Day2>,
+        animate: solution::animate::<Day2::Day2>,
+    },
+    Day {
+        number: 3,
+        input: Input::File("input_day3.txt"),
+        solve: solution::solve::<Day3::Day3>,
+        picture: solution::picture::<Day3::Day3>,
+        animate: solution::animate::<Day3::Day3>,
+    },
+    Day {
+        number: 4,
+        input: Input::Embedded(Day4::INPUT),
+        solve: solution::solve::<Day4::Day4>,
+        picture: solution::picture::<Day4::Day4>,
+        animate: solution::animate::<Day4::Day4>,
+    },
+    Day {
+        number: 5,
+        input: Input::File("input_day5.txt"),
+        solve: solution::solve::<Day5::Day5>,
+        picture: solution::picture::<Day5::Day5>,
+        animate: solution::animate::<Day5::Day5>,
+    },
+    Day {
+        number: 6,
+        input: Input::File("input_day6.txt"),
+        solve: solution::solve::<Day6::Day6>,
+        picture: solution::picture::<Day6::Day6>,
+        animate: solution::animate::<Day6::Day6>,
+    },
+    Day {
+        number: 7,
+        input: Input::File("input_day7.txt"),
+        solve: solution::solve::<Day7::Day7>,
+        picture: solution::picture::<Day7::Day7>,
+        animate: solution::animate::<Day7::Day7>,
+    },
+    Day {
+        number: 8,
+        input: Input::File("input_day8.txt"),
+        solve: solution::solve::<Day8::Day8>,
+        picture: solution::picture::<Day8::Day8>,
+        animate: solution::animate::<Day8::Day8>,
+    },
+    Day {
+        number: 9,
+        input: Input::File("input_day9.txt"),
+        solve: solution::solve::<Day9::Day9>,
+        picture: solution::picture::<Day9::Day9>,
+        animate: solution::animate::<Day9::Day9>,
+    },
+    Day {
+        number: 10,
+        input: Input::File("input_day10.txt"),
+        solve: solution::solve::<Day10::Day10>,
+        picture: solution::picture::<Day10::Day10>,
+        animate: solution::animate::<Day10::Day10>,
+    },
+    Day {
+        number: 11,
+        input: Input::File("input_day11.txt"),
+        solve: solution::solve::<Day11::Day11>,
+        picture: solution::picture::<Day11::Day11>,
+        animate: solution::animate::<Day11::Day11>,
+    },
+    Day {
+        number: 12,
+        input: Input::Embedded(Day12::INPUT),
+        solve: solution::solve::<Day12::Day12>,
+        picture: solution::picture::<Day12::Day12>,
+        animate: solution::animate::<Day12::Day12>,
+    },
+    Day {
+        number: 13,
+        input: Input::File("input_day13.txt"),
+        solve: solution::solve::<Day13::Day13>,
+        picture: solution::picture::<Day13::Day13>,
+        animate: solution::animate::<Day13::Day13>,
+    },
+    Day {
+        number: 14,
+        input: Input::File("input_day14.txt"),
+        solve: solution::solve::<Day14::Day14>,
+        picture: solution::picture::<Day14::Day14>,
+        animate: solution::animate::<Day14::Day14>,
+    },
+    Day {
+        number: 15,
+        input: Input::File("input_day15.txt"),
+        solve: solution::solve::<Day15::Day15>,
+        picture: solution::picture::<Day15::Day15>,
+        animate: solution::animate::<Day15::Day15>,
+    },
+    Day {
+        number: 16,
+        input: Input::Embedded(Day16::INPUT),
+        solve: solution::solve::<Day16::Day16>,
+        picture: solution::picture::<Day16::Day16>,
+        animate: solution::animate::<Day16::Day16>,
+    },
+    Day {
+        number: 17,
+        input: Input::File("input_day17.txt"),
+        solve: solution::solve::<Day17::Day17>,
+        picture: solution::picture::<Day17::Day17>,
+        animate: solution::animate::<Day17::Day17>,
+    },
+];
+
+pub fn find_day(number: u32) -> Option<&'static Day> {
+    DAYS.iter().find(|day| day.number == number)
+}
+
+// Path of the input of the day as shown in messages, None when it is embedded
+pub fn input_path<'a>(day: &'a Day, path: Option<&'a str>) -> Option<&'a str> {
+    match (path, &day.input) {
+        (Some("-"), _) => Some("<stdin>"),
+        (Some(path), _) | (None, &Input::File(path)) => Some(path),
+        (None, Input::Embedded(_)) => None,
+    }
+}
+
+// Input of the day, from the given path or stdin (`-`) when overridden
+pub fn read_input(day: &Day, path: Option<&str>) -> Result<String, String> {
+    match (path, &day.input) {
+        (Some("-"), _) => {
+            let mut input = String::new();
+            io::stdin().read_to_string(&mut input).map_err(|e| format!("Failed to read stdin: {}", e))?;
+            Ok(input)
+        }
+        (Some(path), _) | (None, &Input::File(path)) => fs::read_to_string(path).map_err(|e| format!("Failed to open {}: {}", path, e)),
+        (None, Input::Embedded(text)) => Ok(text.to_string()),
+    }
+}
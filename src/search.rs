@@ -0,0 +1,162 @@
+// Graph searches over any neighbour function.
+//
+// Nodes only need to be hashable, the graph is never built: `neighbours` is called on every node
+// reached and returns the nodes next to it, with the cost of the move for the weighted searches.
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+
+// Distance of every node reached from the start and the node it was reached from
+pub struct Search<N> {
+    pub distances: HashMap<N, u64>,
+    pub predecessors: HashMap<N, N>,
+}
+
+impl<N: Clone + Eq + Hash> Search<N> {
+    fn new(start: N) -> Search<N> {
+        let mut distances = HashMap::new();
+        distances.insert(start.clone(), 0);
+        Search {
+            distances,
+            predecessors: HashMap::new(),
+        }
+    }
+
+    pub fn distance(&self, node: &N) -> Option<u64> {
+        self.distances.get(node).copied()
+    }
+
+    // Nodes from the start to `target` included, None when it was not reached
+    pub fn path(&self, target: &N) -> Option<Vec<N>> {
+        if !self.distances.contains_key(target) {
+            return None;
+        }
+
+        let mut path = vec![target.clone()];
+        while let Some(previous) = self.predecessors.get(path.last().unwrap()) {
+            path.push(previous.clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    // Node furthest from the start and its distance
+    pub fn furthest(&self) -> (&N, u64) {
+        self.distances.iter().map(|(node, &distance)| (node, distance)).max_by_key(|&(_, distance)| distance).unwrap()
+    }
+}
+
+// Every move costs 1
+pub fn bfs<N, F, I>(start: N, mut neighbours: F) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut search = Search::new(start.clone());
+    let mut queue = VecDeque::new();
+    queue.push_back((start, 0));
+
+    while let Some((node, distance)) = queue.pop_front() {
+        for next in neighbours(&node) {
+            if !search.distances.contains_key(&next) {
+                search.distances.insert(next.clone(), distance + 1);
+                search.predecessors.insert(next.clone(), node.clone());
+                queue.push_back((next, distance + 1));
+            }
+        }
+    }
+
+    search
+}
+
+// Node waiting in the heap, ordered by estimated cost only and reversed to pop the cheapest first
+struct Queued<N> {
+    estimate: u64,
+    cost: u64,
+    node: N,
+}
+
+impl<N> PartialEq for Queued<N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.estimate == other.estimate
+    }
+}
+
+impl<N> Eq for Queued<N> {}
+
+impl<N> PartialOrd for Queued<N> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<N> Ord for Queued<N> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.estimate.cmp(&self.estimate)
+    }
+}
+
+// Shared by Dijkstra and A*, stops once `goal` is settled when there is one
+fn best_first<N, F, I, H>(start: N, goal: Option<&N>, mut neighbours: F, heuristic: H) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+    H: Fn(&N) -> u64,
+{
+    let mut search = Search::new(start.clone());
+    let mut heap = BinaryHeap::new();
+    heap.push(Queued {
+        estimate: heuristic(&start),
+        cost: 0,
+        node: start,
+    });
+
+    while let Some(Queued { cost, node, .. }) = heap.pop() {
+        if goal == Some(&node) {
+            break;
+        }
+        if cost > search.distances[&node] {
+            continue;
+        }
+
+        for (next, step) in neighbours(&node) {
+            let next_cost = cost + step;
+            if !matches!(search.distances.get(&next), Some(&known) if known <= next_cost) {
+                search.distances.insert(next.clone(), next_cost);
+                search.predecessors.insert(next.clone(), node.clone());
+                heap.push(Queued {
+                    estimate: next_cost + heuristic(&next),
+                    cost: next_cost,
+                    node: next,
+                });
+            }
+        }
+    }
+
+    search
+}
+
+// Moves have a cost, `neighbours` returns (node, cost) pairs
+pub fn dijkstra<N, F, I>(start: N, neighbours: F) -> Search<N>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+{
+    best_first(start, None, neighbours, |_| 0)
+}
+
+// Cost and path of the cheapest way to `goal`, `heuristic` must never overestimate the remaining cost
+pub fn astar<N, F, I, H>(start: N, goal: &N, neighbours: F, heuristic: H) -> Option<(u64, Vec<N>)>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, u64)>,
+    H: Fn(&N) -> u64,
+{
+    let search = best_first(start, Some(goal), neighbours, heuristic);
+    Some((search.distance(goal)?, search.path(goal)?))
+}
@@ -0,0 +1,221 @@
+// Interface implemented by every day, answers are returned instead of printed so they can be
+// checked, timed or composed by the callers.
+
+use crate::animation::Recorder;
+use crate::export::Raster;
+use crate::intcode::session::{Recording, Sessions};
+use crate::intcode::vm::{Program, RunState, VmError};
+use crate::parse::ParseError;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+pub trait Solution {
+    type Input;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    fn part1(input: &Self::Input) -> Result<Answer, SolveError>;
+
+    fn part2(input: &Self::Input) -> Result<Answer, SolveError>;
+
+    // Solves a part while recording in `sessions` the Intcode programs fed inputs computed on the fly,
+    // the days giving all the input upfront have nothing to record
+    fn solve_part(input: &Self::Input, part: usize, _sessions: &mut Sessions) -> Result<Answer, SolveError> {
+        match part {
+            1 => Self::part1(input),
+            _ => Self::part2(input),
+        }
+    }
+
+    // Picture of the puzzle for the days working on a map or a screen
+    fn picture(_input: &Self::Input) -> Result<Option<Raster>, SolveError> {
+        Ok(None)
+    }
+
+    // Runs the simulation of the day, capturing its frames, false for the days without one
+    fn animate(_input: &Self::Input, _recorder: &mut Recorder) -> Result<bool, SolveError> {
+        Ok(false)
+    }
+}
+
+// Reason a day has no answer for an input it could parse
+#[derive(Clone, Debug, PartialEq)]
+pub enum SolveError {
+    // The input is wrong for the puzzle at this place, also used for the parse errors
+    Input(ParseError),
+    // The Intcode program of the day crashed
+    Intcode(VmError),
+    Failed(String),
+}
+
+impl SolveError {
+    pub fn with_path(self, path: &str) -> SolveError {
+        match self {
+            SolveError::Input(e) => SolveError::Input(e.with_path(path)),
+            e => e,
+        }
+    }
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SolveError::Input(e) => write!(f, "{}", e),
+            SolveError::Intcode(e) => write!(f, "Intcode program failed: {}", e),
+            SolveError::Failed(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+impl From<ParseError> for SolveError {
+    fn from(e: ParseError) -> SolveError {
+        SolveError::Input(e)
+    }
+}
+
+impl From<VmError> for SolveError {
+    fn from(e: VmError) -> SolveError {
+        SolveError::Intcode(e)
+    }
+}
+
+// Runs an Intcode program to its end, the days giving all the input upfront can't let it wait for more
+pub fn run_to_halt(program: &mut Program) -> Result<Vec<i64>, SolveError> {
+    match program.run()? {
+        RunState::Halted => Ok(program.take_output()),
+        RunState::NeedInput => Err(SolveError::Failed(String::from("Intcode program is waiting for more input"))),
+    }
+}
+
+// Last output of a program, the diagnostic code or the answer of several days
+pub fn last_output(output: &[i64]) -> Result<i64, SolveError> {
+    output.last().cloned().ok_or_else(|| SolveError::Failed(String::from("Intcode program produced no output")))
+}
+
+// Monochrome picture, for the puzzles whose answer is drawn by lit pixels
+#[derive(Clone, Debug, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    pub pixels: Vec<bool>,
+}
+
+impl Image {
+    pub fn new(width: usize, height: usize) -> Image {
+        Image {
+            width,
+            height,
+            pixels: vec![false; width * height],
+        }
+    }
+
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        self.pixels[y * self.width + x]
+    }
+
+    pub fn set(&mut self, x: usize, y: usize, lit: bool) {
+        self.pixels[y * self.width + x] = lit;
+    }
+}
+
+impl fmt::Display for Image {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for y in 0..self.height {
+            let line: String = (0..self.width).map(|x| if self.get(x, y) { 'X' } else { ' ' }).collect();
+            writeln!(f, "{}", line.trim_end())?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Answer {
+    Number(i64),
+    Text(String),
+    Image(Image),
+    // Letters read from an image, the image is kept for the JSON reports
+    Lettering { text: String, image: Image },
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::Image(image) => write!(f, "\n{}", image),
+            Answer::Lettering { text, .. } => write!(f, "{}", text),
+        }
+    }
+}
+
+macro_rules! answer_from_int {
+    ($($t:ty),*) => {
+        $(impl From<$t> for Answer {
+            fn from(n: $t) -> Answer {
+                Answer::Number(n as i64)
+            }
+        })*
+    };
+}
+
+answer_from_int!(i32, i64, u32, u64, usize);
+
+impl From<String> for Answer {
+    fn from(s: String) -> Answer {
+        Answer::Text(s)
+    }
+}
+
+impl From<Image> for Answer {
+    fn from(image: Image) -> Answer {
+        Answer::Image(image)
+    }
+}
+
+pub struct PartResult {
+    pub part: usize,
+    pub answer: Result<Answer, SolveError>,
+    pub time: Duration,
+    // Programs recorded while solving the part
+    pub recordings: Vec<Recording>,
+}
+
+pub struct SolveResult {
+    pub parse_time: Duration,
+    pub parts: Vec<PartResult>,
+}
+
+// Parses the input and solves the requested parts (1 and/or 2), timing each step. A part failing
+// doesn't keep the other one from running.
+pub fn solve<S: Solution>(input: &str, parts: &[usize], sessions: &mut Sessions) -> Result<SolveResult, ParseError> {
+    let start = Instant::now();
+    let input = S::parse(input)?;
+    let parse_time = start.elapsed();
+
+    let parts = parts
+        .iter()
+        .map(|&part| {
+            let start = Instant::now();
+            let answer = S::solve_part(&input, part, sessions);
+            let time = start.elapsed();
+            PartResult {
+                part,
+                answer,
+                time,
+                recordings: sessions.take(),
+            }
+        })
+        .collect();
+
+    Ok(SolveResult { parse_time, parts })
+}
+
+pub fn picture<S: Solution>(input: &str) -> Result<Option<Raster>, SolveError> {
+    S::picture(&S::parse(input)?)
+}
+
+pub fn animate<S: Solution>(input: &str, recorder: &mut Recorder) -> Result<bool, SolveError> {
+    S::animate(&S::parse(input)?, recorder)
+}
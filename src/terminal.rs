@@ -0,0 +1,85 @@
+// Live drawing of the simulations in the terminal with ANSI escape sequences.
+//
+// Frames are redrawn in place from the top left corner of the screen, two rows of cells per line of
+// text using upper half blocks with the top cell as foreground and the bottom one as background
+// colour (24-bit colours). The canvas only grows, so sparse grids don't move around as they expand.
+// Frames coming faster than the requested frame rate are skipped rather than waited for, so the
+// simulation isn't slowed down. The cursor is hidden meanwhile and restored when the terminal is
+// dropped.
+
+use crate::animation::Frame;
+use crate::export::{Rgb, BLACK};
+use crate::grid::Point;
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_FPS: u32 = 60;
+
+pub struct Terminal {
+    interval: Duration,
+    next_frame: Option<Instant>,
+    canvas: Option<(Point, Point)>,
+}
+
+impl Terminal {
+    pub fn new(fps: u32) -> Terminal {
+        Terminal {
+            interval: Duration::from_secs(1) / fps,
+            next_frame: None,
+            canvas: None,
+        }
+    }
+
+    // Whether a frame drawn now would keep the frame rate
+    pub fn is_due(&self) -> bool {
+        self.next_frame.is_none_or(|next_frame| Instant::now() >= next_frame)
+    }
+
+    pub fn draw(&mut self, frame: &Frame) {
+        let (origin, end) = (frame.origin(), frame.end());
+        let canvas = match self.canvas {
+            Some((min, max)) => ((min.0.min(origin.0), min.1.min(origin.1)), (max.0.max(end.0), max.1.max(end.1))),
+            None => (origin, end),
+        };
+
+        let mut text = String::new();
+        if self.canvas != Some(canvas) {
+            // First frame or the canvas grew, what was drawn is in the wrong place
+            text.push_str("\x1b[?25l\x1b[2J");
+            self.canvas = Some(canvas);
+        }
+        text.push_str("\x1b[H");
+
+        let (min, max) = canvas;
+        let raster = frame.placed(min, ((max.0 - min.0) as usize, (max.1 - min.1) as usize));
+        let mut colours: Option<(Rgb, Rgb)> = None;
+        for y in (0..raster.height).step_by(2) {
+            for x in 0..raster.width {
+                let top = raster.get(x, y);
+                let bottom = if y + 1 < raster.height { raster.get(x, y + 1) } else { BLACK };
+                if colours != Some((top, bottom)) {
+                    text += &format!("\x1b[38;2;{};{};{}m\x1b[48;2;{};{};{}m", top[0], top[1], top[2], bottom[0], bottom[1], bottom[2]);
+                    colours = Some((top, bottom));
+                }
+                text.push('▀');
+            }
+            text.push_str("\x1b[0m\x1b[K\n");
+            colours = None;
+        }
+
+        let stdout = io::stdout();
+        let mut stdout = stdout.lock();
+        // The picture is only a bonus, a closed terminal is no reason to stop
+        let _ = stdout.write_all(text.as_bytes()).and_then(|_| stdout.flush());
+        self.next_frame = Some(Instant::now() + self.interval);
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        if self.canvas.is_some() {
+            print!("\x1b[0m\x1b[?25h");
+            let _ = io::stdout().flush();
+        }
+    }
+}
@@ -0,0 +1,47 @@
+// Helpers shared by the tests of the days.
+//
+// Property tests draw their cases from a small xorshift generator seeded with the case number, a
+// failing case is reported with its seed and always draws the same values when run again.
+
+use crate::solution::{Answer, Solution};
+use std::ops::Range;
+use std::panic::{self, AssertUnwindSafe};
+
+pub const CASES: u64 = 200;
+
+pub fn part1<S: Solution>(input: &str) -> Answer {
+    S::part1(&S::parse(input).unwrap_or_else(|e| panic!("{}", e))).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub fn part2<S: Solution>(input: &str) -> Answer {
+    S::part2(&S::parse(input).unwrap_or_else(|e| panic!("{}", e))).unwrap_or_else(|e| panic!("{}", e))
+}
+
+pub struct Rng(u64);
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        // The state must never be 0
+        Rng(seed.wrapping_mul(0x9e37_79b9_7f4a_7c15) | 1)
+    }
+
+    pub fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    pub fn range(&mut self, range: Range<i64>) -> i64 {
+        range.start + (self.next() % (range.end - range.start) as u64) as i64
+    }
+}
+
+// Runs the property on CASES generators, panicking with the seed of the first failing case
+pub fn check<F: Fn(&mut Rng)>(property: F) {
+    for seed in 0..CASES {
+        if panic::catch_unwind(AssertUnwindSafe(|| property(&mut Rng::new(seed)))).is_err() {
+            panic!("property failed with seed {}", seed);
+        }
+    }
+}